@@ -0,0 +1,358 @@
+use embedded_hal::PwmPin;
+
+/// Maximum brightness level (percent)
+pub const MAX_LEVEL: u8 = 100;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BacklightError {
+    WrongValue
+}
+
+/// State of backlight by idle policy
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BacklightState {
+    /// Configured brightness
+    Active,
+    /// Dim brightness after idle period
+    Dimmed,
+    /// Switched off after idle period or by `off`
+    Off
+}
+
+/// Backlight with led wired to timer channel
+///
+/// Time is driven by `update`, which must be called periodically
+/// (for example from system tick) with elapsed milliseconds.
+///
+/// # Example
+///
+/// ```no_run
+/// # use embedded_hal::PwmPin;
+/// # use embedded_hal::blocking::delay::DelayMs;
+/// # struct Pwm(u16);
+/// # impl PwmPin for Pwm {
+/// #     type Duty = u16;
+/// #     fn disable(&mut self) {}
+/// #     fn enable(&mut self) {}
+/// #     fn get_duty(&self) -> u16 { self.0 }
+/// #     fn get_max_duty(&self) -> u16 { 1000 }
+/// #     fn set_duty(&mut self, duty: u16) { self.0 = duty; }
+/// # }
+/// # struct Delay;
+/// # impl DelayMs<u16> for Delay { fn delay_ms(&mut self, _ms: u16) {} }
+/// # fn button_pressed() -> bool { false }
+/// # let (pwm, mut delay) = (Pwm(0), Delay);
+/// use device_drivers::display::backlight::Backlight;
+///
+/// let mut backlight = Backlight::new(pwm)
+///     .brightness(80)
+///     .dim_level(10)
+///     .fade_time(500)
+///     .dim_after(30_000)
+///     .off_after(120_000)
+///     .build();
+///
+/// backlight.on();
+///
+/// loop {
+///     if button_pressed() { backlight.activity(); }
+///     backlight.update(10);
+///     delay.delay_ms(10_u16);
+/// }
+/// ```
+pub struct Backlight<PwmType> {
+    pwm: PwmType,
+    brightness: u8,
+    dim_level: u8,
+    fade_time: u32,
+    dim_after: Option<u32>,
+    off_after: Option<u32>,
+
+    state: BacklightState,
+    enabled: bool,
+    idle_time: u32,
+    level: u8,
+    target: u8,
+    fade_accum: u32
+}
+
+impl<PwmType> Backlight<PwmType>
+    where
+        PwmType: PwmPin<Duty = u16>
+{
+    /// Return new backlight instance, switched off
+    ///
+    /// # Arguments
+    ///
+    /// * `pwm` - timer channel of backlight led
+    pub fn new(pwm: PwmType) -> Self {
+        Backlight {
+            pwm,
+            brightness: MAX_LEVEL,
+            dim_level: MAX_LEVEL / 10,
+            fade_time: 0_u32,
+            dim_after: None,
+            off_after: None,
+            state: BacklightState::Off,
+            enabled: false,
+            idle_time: 0_u32,
+            level: 0_u8,
+            target: 0_u8,
+            fade_accum: 0_u32
+        }
+    }
+
+    /// Set brightness level [0-100] of active state
+    pub fn brightness(mut self, level: u8) -> Self {
+        self.brightness = level.min(MAX_LEVEL);
+        self
+    }
+
+    /// Set brightness level [0-100] of dimmed state
+    pub fn dim_level(mut self, level: u8) -> Self {
+        self.dim_level = level.min(MAX_LEVEL);
+        self
+    }
+
+    /// Set time in milliseconds of fade from 0 to 100 level (0 - no fade)
+    pub fn fade_time(mut self, time_ms: u32) -> Self {
+        self.fade_time = time_ms;
+        self
+    }
+
+    /// Set idle time in milliseconds before dimming
+    pub fn dim_after(mut self, time_ms: u32) -> Self {
+        self.dim_after = Some(time_ms);
+        self
+    }
+
+    /// Set idle time in milliseconds before switching off
+    pub fn off_after(mut self, time_ms: u32) -> Self {
+        self.off_after = Some(time_ms);
+        self
+    }
+
+    /// Complete configure backlight
+    pub fn build(self) -> Self {
+        self
+    }
+
+    /// Release timer channel
+    pub fn release(self) -> PwmType {
+        self.pwm
+    }
+
+    /// Current state
+    pub fn state(&self) -> BacklightState {
+        self.state
+    }
+
+    /// Current output level [0-100]
+    pub fn level(&self) -> u8 {
+        self.level
+    }
+
+    /// Change brightness level [0-100] of active state
+    pub fn set_brightness(&mut self, level: u8) -> Result<(), BacklightError> {
+        if level > MAX_LEVEL { return Err(BacklightError::WrongValue); }
+
+        self.brightness = level;
+        self.retarget();
+
+        Ok(())
+    }
+
+    /// Change brightness level [0-100] of dimmed state
+    pub fn set_dim_level(&mut self, level: u8) -> Result<(), BacklightError> {
+        if level > MAX_LEVEL { return Err(BacklightError::WrongValue); }
+
+        self.dim_level = level;
+        self.retarget();
+
+        Ok(())
+    }
+
+    /// Fade in to configured brightness
+    pub fn on(&mut self) {
+        self.enabled = true;
+        self.activity();
+    }
+
+    /// Fade out, idle policy and activity do not switch on until `on`
+    pub fn off(&mut self) {
+        self.enabled = false;
+        self.state = BacklightState::Off;
+        self.retarget();
+    }
+
+    /// Notify about user activity, restart idle period
+    pub fn activity(&mut self) {
+        self.idle_time = 0;
+
+        if self.enabled {
+            self.state = BacklightState::Active;
+            self.retarget();
+        }
+    }
+
+    /// Advance idle period and fade
+    ///
+    /// # Arguments
+    ///
+    /// * `elapsed_ms` - milliseconds since previous call
+    pub fn update(&mut self, elapsed_ms: u32) {
+        if self.enabled {
+            self.idle_time = self.idle_time.saturating_add(elapsed_ms);
+            self.apply_idle_policy();
+        }
+
+        self.fade(elapsed_ms);
+    }
+
+    fn apply_idle_policy(&mut self) {
+        let state = match (self.off_after, self.dim_after) {
+            (Some(off_after), _) if self.idle_time >= off_after => BacklightState::Off,
+            (_, Some(dim_after)) if self.idle_time >= dim_after => BacklightState::Dimmed,
+            _ => self.state
+        };
+
+        if state != self.state {
+            self.state = state;
+            self.retarget();
+        }
+    }
+
+    fn retarget(&mut self) {
+        self.target = match self.state {
+            BacklightState::Active => self.brightness,
+            BacklightState::Dimmed => self.dim_level.min(self.brightness),
+            BacklightState::Off => 0_u8
+        };
+
+        if self.fade_time == 0 {
+            self.level = self.target;
+            self.output();
+        }
+    }
+
+    fn fade(&mut self, elapsed_ms: u32) {
+        if self.level == self.target {
+            self.fade_accum = 0;
+            return;
+        }
+
+        self.fade_accum = self.fade_accum.saturating_add(elapsed_ms.saturating_mul(MAX_LEVEL as u32));
+        let steps = (self.fade_accum / self.fade_time).min(MAX_LEVEL as u32) as u8;
+        self.fade_accum %= self.fade_time;
+
+        self.level = if self.level < self.target {
+            self.level.saturating_add(steps).min(self.target)
+        } else {
+            self.level.saturating_sub(steps).max(self.target)
+        };
+
+        self.output();
+    }
+
+    fn output(&mut self) {
+        if self.level == 0 {
+            self.pwm.set_duty(0);
+            self.pwm.disable();
+            return;
+        }
+
+        let duty = (self.pwm.get_max_duty() as u32) * (self.level as u32) / (MAX_LEVEL as u32);
+
+        self.pwm.set_duty(duty as u16);
+        self.pwm.enable();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct DumpPwm {
+        duty: u16,
+        enabled: bool
+    }
+
+    impl PwmPin for DumpPwm {
+        type Duty = u16;
+
+        fn disable(&mut self) { self.enabled = false; }
+        fn enable(&mut self) { self.enabled = true; }
+        fn get_duty(&self) -> u16 { self.duty }
+        fn get_max_duty(&self) -> u16 { 1000 }
+        fn set_duty(&mut self, duty: u16) { self.duty = duty; }
+    }
+
+    fn backlight() -> Backlight<DumpPwm> {
+        Backlight::new(DumpPwm { duty: 0, enabled: false })
+            .brightness(80)
+            .dim_level(20)
+            .fade_time(1000)
+            .dim_after(5000)
+            .off_after(10000)
+            .build()
+    }
+
+    #[test]
+    fn test_fade_in() {
+        let mut backlight = backlight();
+
+        backlight.on();
+        backlight.update(500);
+        assert_eq!(backlight.level(), 50);
+
+        backlight.update(500);
+        assert_eq!(backlight.level(), 80);
+
+        let pwm = backlight.release();
+        assert_eq!(pwm.duty, 800);
+        assert!(pwm.enabled);
+    }
+
+    #[test]
+    fn test_idle_policy() {
+        let mut backlight = backlight();
+
+        backlight.on();
+        backlight.update(5000);
+        assert_eq!(backlight.state(), BacklightState::Dimmed);
+        backlight.update(1000);
+        assert_eq!(backlight.level(), 20);
+
+        backlight.update(4000);
+        assert_eq!(backlight.state(), BacklightState::Off);
+        backlight.update(1000);
+        assert_eq!(backlight.level(), 0);
+        assert!(!backlight.release().enabled);
+    }
+
+    #[test]
+    fn test_activity_after_off() {
+        let mut backlight = backlight();
+
+        backlight.on();
+        backlight.off();
+        backlight.activity();
+        assert_eq!(backlight.state(), BacklightState::Off);
+
+        backlight.on();
+        assert_eq!(backlight.state(), BacklightState::Active);
+    }
+
+    #[test]
+    fn test_set_levels() {
+        let mut backlight = backlight();
+
+        backlight.on();
+        backlight.set_brightness(60).unwrap();
+        backlight.update(1000);
+        assert_eq!(backlight.level(), 60);
+
+        assert_eq!(backlight.set_brightness(101), Err(BacklightError::WrongValue));
+        assert_eq!(backlight.set_dim_level(101), Err(BacklightError::WrongValue));
+    }
+}
@@ -0,0 +1,301 @@
+use embedded_hal::PwmPin;
+use embedded_hal::blocking::i2c::{Read, Write};
+use crate::i2c::mcp401x::{Mcp401x, MAX_WIPER};
+
+/// Maximum contrast level (percent)
+pub const MAX_LEVEL: u8 = 100;
+
+#[derive(Debug)]
+pub enum ContrastError {
+    WrongValue,
+    OutputError
+}
+
+/// Output which drives V0 pin of lcd
+pub trait ContrastOutput {
+    /// Maximum raw value (V0 at supply)
+    fn max_value(&self) -> u16;
+
+    /// Write raw value [0-max_value] (0 - V0 at ground)
+    fn set_value(&mut self, value: u16) -> Result<(), ContrastError>;
+}
+
+/// Timer channel with RC filter on V0 pin
+pub struct PwmOutput<PwmType> {
+    pwm: PwmType
+}
+
+impl<PwmType> PwmOutput<PwmType>
+    where
+        PwmType: PwmPin<Duty = u16>
+{
+    pub fn new(mut pwm: PwmType) -> Self {
+        pwm.enable();
+
+        PwmOutput {
+            pwm
+        }
+    }
+
+    /// Release timer channel
+    pub fn release(self) -> PwmType {
+        self.pwm
+    }
+}
+
+impl<PwmType> ContrastOutput for PwmOutput<PwmType>
+    where
+        PwmType: PwmPin<Duty = u16>
+{
+    fn max_value(&self) -> u16 {
+        self.pwm.get_max_duty()
+    }
+
+    fn set_value(&mut self, value: u16) -> Result<(), ContrastError> {
+        self.pwm.set_duty(value);
+
+        Ok(())
+    }
+}
+
+impl<I2CType> ContrastOutput for Mcp401x<I2CType>
+    where
+        I2CType: Read + Write
+{
+    fn max_value(&self) -> u16 {
+        MAX_WIPER as u16
+    }
+
+    fn set_value(&mut self, value: u16) -> Result<(), ContrastError> {
+        if value > MAX_WIPER as u16 { return Err(ContrastError::WrongValue); }
+
+        self.set_wiper(value as u8)
+            .map_err(|_| { ContrastError::OutputError })
+    }
+}
+
+/// Contrast of lcd by V0 pin
+///
+/// Level 0 is the lowest contrast, 100 is the highest. By default the
+/// highest contrast is V0 at ground, `inverted` changes it to V0 at supply.
+///
+/// Liquid crystal needs more drive at low temperature, so `auto_adjust`
+/// shifts configured level by `temperature_coefficient` tenths of level per
+/// degree below `reference_temperature` (and back above it).
+///
+/// # Example
+///
+/// ```
+/// # use embedded_hal::PwmPin;
+/// # struct Pwm(u16);
+/// # impl PwmPin for Pwm {
+/// #     type Duty = u16;
+/// #     fn disable(&mut self) {}
+/// #     fn enable(&mut self) {}
+/// #     fn get_duty(&self) -> u16 { self.0 }
+/// #     fn get_max_duty(&self) -> u16 { 1000 }
+/// #     fn set_duty(&mut self, duty: u16) { self.0 = duty; }
+/// # }
+/// # let pwm = Pwm(0);
+/// use device_drivers::display::contrast::{Contrast, PwmOutput};
+///
+/// let mut contrast = Contrast::new(PwmOutput::new(pwm))
+///     .level(60)
+///     .temperature_coefficient(8)
+///     .build();
+///
+/// contrast.apply().unwrap();
+/// contrast.auto_adjust(-20).unwrap();
+/// ```
+pub struct Contrast<OutputType> {
+    output: OutputType,
+    level: u8,
+    inverted: bool,
+    reference_temperature: i8,
+    temperature_coefficient: u8,
+    applied_level: u8
+}
+
+impl<OutputType> Contrast<OutputType>
+    where
+        OutputType: ContrastOutput
+{
+    pub fn new(output: OutputType) -> Self {
+        Contrast {
+            output,
+            level: MAX_LEVEL / 2,
+            inverted: false,
+            reference_temperature: 25_i8,
+            temperature_coefficient: 0_u8,
+            applied_level: MAX_LEVEL / 2
+        }
+    }
+
+    /// Set configured level [0-100]
+    pub fn level(mut self, level: u8) -> Self {
+        self.level = level.min(MAX_LEVEL);
+        self
+    }
+
+    /// Highest contrast is V0 at supply
+    pub fn inverted(mut self) -> Self {
+        self.inverted = true;
+        self
+    }
+
+    /// Set temperature (°C) at which configured level is applied as is
+    pub fn reference_temperature(mut self, temperature: i8) -> Self {
+        self.reference_temperature = temperature;
+        self
+    }
+
+    /// Set tenths of level added per degree below reference temperature
+    pub fn temperature_coefficient(mut self, coefficient: u8) -> Self {
+        self.temperature_coefficient = coefficient;
+        self
+    }
+
+    /// Complete configure contrast
+    pub fn build(self) -> Self {
+        self
+    }
+
+    /// Release output
+    pub fn release(self) -> OutputType {
+        self.output
+    }
+
+    /// Configured level [0-100]
+    pub fn get_level(&self) -> u8 {
+        self.level
+    }
+
+    /// Level [0-100] written to output, after auto adjust
+    pub fn get_applied_level(&self) -> u8 {
+        self.applied_level
+    }
+
+    /// Store and write configured level [0-100]
+    pub fn set_level(&mut self, level: u8) -> Result<(), ContrastError> {
+        if level > MAX_LEVEL { return Err(ContrastError::WrongValue); }
+
+        self.level = level;
+        self.apply()
+    }
+
+    /// Write configured level without temperature adjust
+    pub fn apply(&mut self) -> Result<(), ContrastError> {
+        self.write_level(self.level)
+    }
+
+    /// Write configured level adjusted for temperature
+    ///
+    /// # Arguments
+    ///
+    /// * `temperature` - ambient temperature in °C (for example from DS3231)
+    pub fn auto_adjust(&mut self, temperature: i8) -> Result<(), ContrastError> {
+        let delta = (self.reference_temperature as i32 - temperature as i32)
+            * self.temperature_coefficient as i32 / 10;
+
+        let level = (self.level as i32 + delta).max(0).min(MAX_LEVEL as i32);
+
+        self.write_level(level as u8)
+    }
+
+    fn write_level(&mut self, level: u8) -> Result<(), ContrastError> {
+        let max_value = self.output.max_value() as u32;
+        let drive = max_value * level as u32 / MAX_LEVEL as u32;
+
+        let value = match self.inverted {
+            false => max_value - drive,
+            true => drive
+        };
+
+        self.output.set_value(value as u16)?;
+        self.applied_level = level;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i2c::mcp401x::DEFAULT_ADDRESS;
+    use crate::i2c::mock::DumpI2c;
+
+    struct DumpOutput {
+        value: u16
+    }
+
+    impl ContrastOutput for DumpOutput {
+        fn max_value(&self) -> u16 { 1000 }
+
+        fn set_value(&mut self, value: u16) -> Result<(), ContrastError> {
+            self.value = value;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_auto_adjust() {
+        let mut contrast = Contrast::new(DumpOutput { value: 0 })
+            .level(60)
+            .temperature_coefficient(5)
+            .build();
+
+        contrast.apply().unwrap();
+        assert_eq!(contrast.get_applied_level(), 60);
+
+        contrast.auto_adjust(-15).unwrap();
+        assert_eq!(contrast.get_applied_level(), 80);
+        assert_eq!(contrast.get_level(), 60);
+
+        contrast.auto_adjust(127).unwrap();
+        assert_eq!(contrast.get_applied_level(), 9);
+        assert_eq!(contrast.release().value, 910);
+    }
+
+    #[test]
+    fn test_inverted() {
+        let mut contrast = Contrast::new(DumpOutput { value: 0 })
+            .level(30)
+            .build();
+
+        contrast.apply().unwrap();
+        assert_eq!(contrast.release().value, 700);
+
+        let mut contrast = Contrast::new(DumpOutput { value: 0 })
+            .level(30)
+            .inverted()
+            .build();
+
+        contrast.apply().unwrap();
+        assert_eq!(contrast.release().value, 300);
+    }
+
+    #[test]
+    fn test_mcp401x_output() {
+        let i2c = DumpI2c::new(DEFAULT_ADDRESS, 1).pointer_size(0);
+        let mut contrast = Contrast::new(Mcp401x::new(i2c, DEFAULT_ADDRESS))
+            .level(100)
+            .build();
+
+        contrast.apply().unwrap();
+        assert_eq!(contrast.release().release().registers[0], 0);
+
+        let i2c = DumpI2c::new(DEFAULT_ADDRESS, 1).pointer_size(0);
+        let mut contrast = Contrast::new(Mcp401x::new(i2c, DEFAULT_ADDRESS))
+            .level(50)
+            .inverted()
+            .build();
+
+        contrast.apply().unwrap();
+        assert_eq!(contrast.release().release().registers[0], 63);
+
+        let i2c = DumpI2c::new(DEFAULT_ADDRESS, 1).pointer_size(0);
+        let mut pot = Mcp401x::new(i2c, DEFAULT_ADDRESS);
+        assert_eq!(pot.max_value(), MAX_WIPER as u16);
+        assert!(matches!(pot.set_value(128), Err(ContrastError::WrongValue)));
+    }
+}
@@ -0,0 +1,148 @@
+use core::convert::Infallible;
+use super::traits::{TextDisplay, DisplayError};
+
+const GLYPH_COUNT: u8 = 8;
+
+/// Text display in memory, for host simulator and tests
+///
+/// Custom glyphs are stored in cells like on HD44780: glyph from
+/// location `n` is char with code `n`.
+///
+/// # Example
+///
+/// ```
+/// use device_drivers::display::memory::MemoryDisplay;
+/// use device_drivers::display::traits::TextDisplay;
+///
+/// let mut display = MemoryDisplay::<16, 2>::new();
+/// display.put_str(0, 0, "Hello").unwrap();
+/// assert_eq!(display.char_at(1, 0), Some('e'));
+/// ```
+pub struct MemoryDisplay<const COLS: usize, const ROWS: usize> {
+    cells: [[char; COLS]; ROWS],
+    glyphs: [[u8; 8]; GLYPH_COUNT as usize]
+}
+
+impl<const COLS: usize, const ROWS: usize> MemoryDisplay<COLS, ROWS> {
+    pub fn new() -> Self {
+        MemoryDisplay {
+            cells: [[' '; COLS]; ROWS],
+            glyphs: [[0_u8; 8]; GLYPH_COUNT as usize]
+        }
+    }
+
+    /// Read char from cell (column, row)
+    pub fn char_at(&self, col: u8, row: u8) -> Option<char> {
+        self.cells.get(row as usize)?.get(col as usize).copied()
+    }
+
+    /// Read all cells of row
+    pub fn row(&self, row: u8) -> Option<&[char; COLS]> {
+        self.cells.get(row as usize)
+    }
+
+    /// Read custom glyph by location
+    pub fn glyph(&self, location: u8) -> Option<&[u8; 8]> {
+        self.glyphs.get(location as usize)
+    }
+
+    fn cell_mut(&mut self, col: u8, row: u8) -> Result<&mut char, DisplayError<Infallible>> {
+        self.cells.get_mut(row as usize)
+            .and_then(|cells| cells.get_mut(col as usize))
+            .ok_or(DisplayError::OutOfRange)
+    }
+}
+
+impl<const COLS: usize, const ROWS: usize> Default for MemoryDisplay<COLS, ROWS> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const COLS: usize, const ROWS: usize> TextDisplay for MemoryDisplay<COLS, ROWS> {
+    type Error = Infallible;
+
+    fn column_count(&self) -> u8 {
+        COLS as u8
+    }
+
+    fn row_count(&self) -> u8 {
+        ROWS as u8
+    }
+
+    fn glyph_count(&self) -> u8 {
+        GLYPH_COUNT
+    }
+
+    fn clear_all(&mut self) -> Result<(), DisplayError<Self::Error>> {
+        self.cells = [[' '; COLS]; ROWS];
+
+        Ok(())
+    }
+
+    fn put_char(&mut self, col: u8, row: u8, data: char) -> Result<(), DisplayError<Self::Error>> {
+        *self.cell_mut(col, row)? = data;
+
+        Ok(())
+    }
+
+    fn create_glyph(&mut self, location: u8, glyph: &[u8; 8]) -> Result<(), DisplayError<Self::Error>> {
+        if location >= GLYPH_COUNT { return Err(DisplayError::OutOfRange); }
+
+        for (row, &bits) in self.glyphs[location as usize].iter_mut().zip(glyph.iter()) {
+            *row = bits & 0x1F;
+        }
+
+        Ok(())
+    }
+
+    fn put_glyph(&mut self, col: u8, row: u8, location: u8) -> Result<(), DisplayError<Self::Error>> {
+        if location >= GLYPH_COUNT { return Err(DisplayError::OutOfRange); }
+
+        *self.cell_mut(col, row)? = char::from(location);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type TestDisplay = MemoryDisplay<16, 2>;
+
+    #[test]
+    fn test_put_str_clipped() {
+        let mut display = TestDisplay::new();
+
+        display.put_str(12, 1, "Hello").unwrap();
+
+        assert_eq!(display.char_at(12, 1), Some('H'));
+        assert_eq!(display.char_at(15, 1), Some('l'));
+        assert_eq!(display.char_at(0, 0), Some(' '));
+    }
+
+    #[test]
+    fn test_out_of_range() {
+        let mut display = TestDisplay::new();
+
+        assert_eq!(display.put_char(16, 0, 'A'), Err(DisplayError::OutOfRange));
+        assert_eq!(display.put_str(0, 2, "A"), Err(DisplayError::OutOfRange));
+        assert_eq!(display.create_glyph(8, &[0; 8]), Err(DisplayError::OutOfRange));
+    }
+
+    #[test]
+    fn test_glyph() {
+        let mut display = TestDisplay::new();
+        let glyph = [0xFF_u8, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b11111];
+
+        display.create_glyph(3, &glyph).unwrap();
+        display.put_glyph(0, 0, 3).unwrap();
+
+        assert_eq!(display.glyph(3).unwrap()[0], 0b11111);
+        assert_eq!(display.char_at(0, 0), Some('\u{3}'));
+
+        display.clear_all().unwrap();
+        assert_eq!(display.char_at(0, 0), Some(' '));
+    }
+}
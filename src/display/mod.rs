@@ -0,0 +1,4 @@
+pub mod traits;
+pub mod memory;
+pub mod backlight;
+pub mod contrast;
@@ -0,0 +1,50 @@
+/// Errors of text display
+#[derive(Debug, PartialEq)]
+pub enum DisplayError<E> {
+    /// Cell or glyph location is outside of the display
+    OutOfRange,
+    /// Display has no custom glyphs
+    Unsupported,
+    /// Error of underlying device
+    Device(E)
+}
+
+/// Text display with grid of character cells
+pub trait TextDisplay {
+    /// Error type of underlying device
+    type Error;
+
+    /// Count of columns.
+    fn column_count(&self) -> u8;
+
+    /// Count of rows.
+    fn row_count(&self) -> u8;
+
+    /// Count of custom glyph locations (0 if glyphs are not supported).
+    fn glyph_count(&self) -> u8;
+
+    /// Clear all cells.
+    fn clear_all(&mut self) -> Result<(), DisplayError<Self::Error>>;
+
+    /// Put char to cell (column, row).
+    fn put_char(&mut self, col: u8, row: u8, data: char) -> Result<(), DisplayError<Self::Error>>;
+
+    /// Put string starting from cell (column, row), clipped at end of row.
+    fn put_str(&mut self, col: u8, row: u8, data: &str) -> Result<(), DisplayError<Self::Error>> {
+        if col >= self.column_count() || row >= self.row_count() {
+            return Err(DisplayError::OutOfRange);
+        }
+
+        for (ch, col) in data.chars().zip(col..self.column_count()) {
+            self.put_char(col, row, ch)?;
+        }
+
+        Ok(())
+    }
+
+    /// Create custom glyph by location (8 rows by 5 bits).
+    fn create_glyph(&mut self, location: u8, glyph: &[u8; 8]) -> Result<(), DisplayError<Self::Error>>;
+
+    /// Put custom glyph from location to cell (column, row).
+    fn put_glyph(&mut self, col: u8, row: u8, location: u8) -> Result<(), DisplayError<Self::Error>>;
+}
@@ -0,0 +1,260 @@
+use embedded_hal::blocking::{i2c::{Write, WriteRead}, delay::DelayUs};
+
+/// Address of AT24C32 on ZS-042 DS3231 module (A0-A2 pulled up)
+pub const DEFAULT_ADDRESS: u8 = 0x57;
+
+/// Time budget of write cycle in us, 5-10 ms by datasheets, 20 ms for old
+/// parts at low voltage
+const WRITE_CYCLE_US: u16 = 20_000;
+
+/// Delay between polls of busy eeprom in us
+const POLL_INTERVAL_US: u16 = 100;
+
+const MAX_PAGE_SIZE: usize = 64;
+
+/// Eeprom of AT24Cxx family with two byte memory address
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Model {
+    /// 4 KB, 32 byte page
+    At24c32,
+    /// 8 KB, 32 byte page
+    At24c64,
+    /// 32 KB, 64 byte page
+    At24c256
+}
+
+impl Model {
+    /// Size of memory in bytes
+    pub fn capacity(&self) -> usize {
+        match self {
+            Model::At24c32 => 4096,
+            Model::At24c64 => 8192,
+            Model::At24c256 => 32768
+        }
+    }
+
+    /// Size of write page in bytes
+    pub fn page_size(&self) -> usize {
+        match self {
+            Model::At24c32 | Model::At24c64 => 32,
+            Model::At24c256 => MAX_PAGE_SIZE
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum At24cxError {
+    WrongValue,
+    I2cError,
+    /// Eeprom did not finish write cycle in time
+    Timeout
+}
+
+/// AT24C32/AT24C64/AT24C256 i2c eeprom
+///
+/// # Example
+///
+/// ```no_run
+/// # use embedded_hal::blocking::{delay::DelayUs, i2c::{Write, WriteRead}};
+/// # fn example<I2C, D>(i2c: I2C, delay: D, calibration: [u8; 16])
+/// #     where I2C: Write + WriteRead, D: DelayUs<u16>
+/// # {
+/// use device_drivers::i2c::at24cx::{At24cx, Model, DEFAULT_ADDRESS};
+///
+/// let mut eeprom = At24cx::new(i2c, DEFAULT_ADDRESS, Model::At24c32, delay);
+///
+/// eeprom.write(0x0100, &calibration).unwrap();
+///
+/// let mut data = [0_u8; 16];
+/// eeprom.read(0x0100, &mut data).unwrap();
+/// # }
+/// ```
+pub struct At24cx<I2CType, DelayType> {
+    i2c: I2CType,
+    address: u8,
+    model: Model,
+    delay: DelayType
+}
+
+impl<I2CType, DelayType> At24cx<I2CType, DelayType>
+    where
+        I2CType: Write + WriteRead,
+        DelayType: DelayUs<u16>
+{
+    /// Return new eeprom instance
+    ///
+    /// # Arguments
+    /// * `i2c` - i2c for sending data
+    /// * `address` - address of device (0x50-0x57)
+    /// * `model` - model of eeprom
+    /// * `delay` - delay between polls while write cycle is in progress
+    pub fn new(i2c: I2CType, address: u8, model: Model, delay: DelayType) -> Self {
+        At24cx {
+            i2c,
+            address,
+            model,
+            delay
+        }
+    }
+
+    /// Release i2c and delay
+    pub fn release(self) -> (I2CType, DelayType) {
+        (self.i2c, self.delay)
+    }
+
+    /// Size of memory in bytes
+    pub fn capacity(&self) -> usize {
+        self.model.capacity()
+    }
+
+    fn check(&self, memory_address: u16, length: usize) -> Result<(), At24cxError> {
+        if memory_address as usize + length > self.model.capacity() { return Err(At24cxError::WrongValue); }
+
+        Ok(())
+    }
+
+    /// Poll eeprom by address only write until it acknowledges after write
+    /// cycle
+    fn wait_ready(&mut self) -> Result<(), At24cxError> {
+        let mut elapsed = 0_u16;
+
+        while self.i2c.write(self.address, &[]).is_err() {
+            if elapsed >= WRITE_CYCLE_US { return Err(At24cxError::Timeout); }
+
+            self.delay.delay_us(POLL_INTERVAL_US);
+            elapsed += POLL_INTERVAL_US;
+        }
+
+        Ok(())
+    }
+
+    /// Read bytes in one sequential read
+    ///
+    /// # Arguments
+    ///
+    /// * `memory_address` - address of first byte
+    /// * `data` - buffer, must fit in memory from address
+    pub fn read(&mut self, memory_address: u16, data: &mut [u8]) -> Result<(), At24cxError> {
+        self.check(memory_address, data.len())?;
+
+        self.i2c.write_read(self.address, &memory_address.to_be_bytes(), data)
+            .map_err(|_| { At24cxError::I2cError })
+    }
+
+    /// Write bytes, split at page boundaries, and wait for each write cycle
+    ///
+    /// # Arguments
+    ///
+    /// * `memory_address` - address of first byte
+    /// * `data` - bytes to write, must fit in memory from address
+    pub fn write(&mut self, memory_address: u16, data: &[u8]) -> Result<(), At24cxError> {
+        self.check(memory_address, data.len())?;
+
+        let page_size = self.model.page_size();
+        let mut buffer = [0_u8; MAX_PAGE_SIZE + 2];
+        let mut address = memory_address as usize;
+        let mut data = data;
+
+        while !data.is_empty() {
+            // page write wraps around inside page, so it must not cross page end
+            let length = data.len().min(page_size - address % page_size);
+
+            buffer[..2].copy_from_slice(&(address as u16).to_be_bytes());
+            buffer[2..length + 2].copy_from_slice(&data[..length]);
+
+            self.i2c.write(self.address, &buffer[..length + 2])
+                .map_err(|_| { At24cxError::I2cError })?;
+
+            self.wait_ready()?;
+
+            address += length;
+            data = &data[length..];
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i2c::mock::{Device, DumpI2c, MockDelay};
+
+    #[derive(Default)]
+    struct Eeprom {
+        /// Address NACKs left until write cycle ends
+        busy: u16,
+        /// Page of address written at start of transaction
+        page: usize,
+        pages: usize
+    }
+
+    impl Device for Eeprom {
+        fn start(&mut self, _registers: &mut [u8], bytes: &[u8]) -> Result<(), ()> {
+            if self.busy > 0 {
+                self.busy -= 1;
+                return Err(());
+            }
+
+            if bytes.len() >= 2 {
+                self.page = u16::from_be_bytes([bytes[0], bytes[1]]) as usize / 32;
+            }
+
+            Ok(())
+        }
+
+        fn write(&mut self, registers: &mut [u8], pointer: usize, value: u8) {
+            assert_eq!(pointer / 32, self.page);
+            registers[pointer] = value;
+        }
+
+        fn stop(&mut self, _registers: &mut [u8], bytes: &[u8], read: bool) {
+            if !read && bytes.len() > 2 {
+                self.pages += 1;
+                self.busy = 3;
+            }
+        }
+    }
+
+    fn eeprom() -> At24cx<DumpI2c<Eeprom>, MockDelay> {
+        let mut i2c = DumpI2c::with_device(DEFAULT_ADDRESS, 4096, Eeprom::default()).pointer_size(2);
+        i2c.registers.fill(0xFF);
+
+        At24cx::new(i2c, DEFAULT_ADDRESS, Model::At24c32, MockDelay { elapsed_us: 0 })
+    }
+
+    #[test]
+    fn test_write_pages() {
+        let mut eeprom = eeprom();
+        let data: [u8; 70] = core::array::from_fn(|i| i as u8);
+
+        eeprom.write(0x001E, &data).unwrap();
+        assert_eq!(eeprom.i2c.device.pages, 4);
+        assert_eq!(eeprom.i2c.registers[0x001E..0x001E + 70], data);
+
+        let mut read = [0_u8; 70];
+        eeprom.read(0x001E, &mut read).unwrap();
+        assert_eq!(read, data);
+    }
+
+    #[test]
+    fn test_bounds() {
+        let mut eeprom = eeprom();
+
+        assert!(eeprom.write(0x0FFF, &[1]).is_ok());
+        assert!(matches!(eeprom.write(0x0FFF, &[1, 2]), Err(At24cxError::WrongValue)));
+        assert!(matches!(eeprom.read(0x1000, &mut [0_u8]), Err(At24cxError::WrongValue)));
+    }
+
+    #[test]
+    fn test_timeout() {
+        let mut eeprom = eeprom();
+
+        eeprom.i2c.device.busy = u16::MAX;
+        assert!(matches!(eeprom.wait_ready(), Err(At24cxError::Timeout)));
+        assert_eq!(eeprom.delay.elapsed_us, WRITE_CYCLE_US as u32);
+
+        eeprom.i2c.device.busy = 0;
+        assert!(eeprom.wait_ready().is_ok());
+    }
+}
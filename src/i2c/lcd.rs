@@ -1,4 +1,5 @@
 use embedded_hal::blocking::{i2c::{Write}, delay::DelayMs};
+use crate::display::traits::{TextDisplay, DisplayError};
 
 const BACKLIGHT: u8 = 0b0000_1000;
 const NO_BACKLIGHT: u8 = 0b0000_0000;
@@ -30,6 +31,8 @@ const SET_CRAM_ADDR: u8 = 0b0100_0000;
 
 const INITIALIZE_4BIT: u8 = 0x33;
 
+const GLYPH_COUNT: u8 = 8;
+
 pub trait LcdTrait {
     fn init(&mut self) -> Result<(), ()>;
     fn clear(&mut self) -> Result<(), ()>;
@@ -51,7 +54,9 @@ pub trait LcdTrait {
 ///
 /// # Example
 ///
-/// ```
+/// ```no_run
+/// # use embedded_hal::blocking::{delay::DelayMs, i2c::Write};
+/// # fn example<I2C, D>(i2c: I2C, led_delay: D) where I2C: Write, D: DelayMs<u16> {
 /// use device_drivers::i2c::lcd::{LcdTrait, Lcd};
 /// let mut lcd = Lcd::new(
 ///         i2c,
@@ -65,6 +70,7 @@ pub trait LcdTrait {
 /// lcd.init().unwrap();
 /// lcd.reset().unwrap();
 /// lcd.clear().unwrap();
+/// # }
 /// ```
 ///
 #[derive(Default)]
@@ -95,9 +101,12 @@ impl<I2cType, DelayType> Lcd<I2cType, DelayType>
     /// * `delay` - variable for call delay_ms
     ///
     /// # Example
-    /// ```
+    /// ```no_run
+    /// # use embedded_hal::blocking::{delay::DelayMs, i2c::Write};
+    /// # fn example<I2C, D>(i2c: I2C, delay: D) where I2C: Write, D: DelayMs<u16> {
     /// use device_drivers::i2c::lcd::{Lcd};
     /// let lcd = Lcd::new(i2c, 0x27, delay).build();
+    /// # }
     /// ```
     pub fn new(
         i2c: I2cType,
@@ -130,11 +139,14 @@ impl<I2cType, DelayType> Lcd<I2cType, DelayType>
     ///
     /// # Example
     ///
-    /// ```
+    /// ```no_run
+    /// # use embedded_hal::blocking::{delay::DelayMs, i2c::Write};
+    /// # fn example<I2C, D>(i2c: I2C, delay: D) where I2C: Write, D: DelayMs<u16> {
     /// use device_drivers::i2c::lcd::{Lcd};
     /// let lcd = Lcd::new(i2c, 0x27, delay)
     ///     .columns(20)
     ///     .build();
+    /// # }
     /// ```
     pub fn columns(mut self, cols: u8) -> Self {
         self.cols = cols;
@@ -152,11 +164,14 @@ impl<I2cType, DelayType> Lcd<I2cType, DelayType>
     ///
     /// # Example
     ///
-    /// ```
+    /// ```no_run
+    /// # use embedded_hal::blocking::{delay::DelayMs, i2c::Write};
+    /// # fn example<I2C, D>(i2c: I2C, delay: D) where I2C: Write, D: DelayMs<u16> {
     /// use device_drivers::i2c::lcd::{Lcd};
     /// let lcd = Lcd::new(i2c, 0x27, delay)
     ///     .rows(4)
     ///     .build();
+    /// # }
     /// ```
     pub fn rows(mut self, rows: u8) -> Self {
         self.rows = rows;
@@ -175,11 +190,14 @@ impl<I2cType, DelayType> Lcd<I2cType, DelayType>
     ///
     /// # Example
     ///
-    /// ```
+    /// ```no_run
+    /// # use embedded_hal::blocking::{delay::DelayMs, i2c::Write};
+    /// # fn example<I2C, D>(i2c: I2C, delay: D) where I2C: Write, D: DelayMs<u16> {
     /// use device_drivers::i2c::lcd::{Lcd};
     /// let lcd = Lcd::new(i2c, 0x27, delay)
     ///     .char_size(1)
     ///     .build();
+    /// # }
     /// ```
     pub fn char_size(mut self, char_size: u8) -> Self {
         self.char_size = char_size;
@@ -194,10 +212,13 @@ impl<I2cType, DelayType> Lcd<I2cType, DelayType>
     ///
     /// # Example
     ///
-    /// ```
+    /// ```no_run
+    /// # use embedded_hal::blocking::{delay::DelayMs, i2c::Write};
+    /// # fn example<I2C, D>(i2c: I2C, delay: D) where I2C: Write, D: DelayMs<u16> {
     /// use device_drivers::i2c::lcd::{Lcd};
     /// let lcd = Lcd::new(i2c, 0x27, delay)
     ///     .build();
+    /// # }
     /// ```
     pub fn build(self) -> Self {
         self
@@ -275,11 +296,14 @@ impl<I2cType, DelayType> LcdTrait for Lcd<I2cType, DelayType>
     ///
     /// # Example
     ///
-    /// ```
+    /// ```no_run
+    /// # use embedded_hal::blocking::{delay::DelayMs, i2c::Write};
+    /// # fn example<I2C, D>(i2c: I2C, delay: D) where I2C: Write, D: DelayMs<u16> {
     /// use device_drivers::i2c::lcd::{LcdTrait, Lcd};
     ///
     /// let mut lcd = Lcd::new(i2c, 0x27, delay).build();
     /// lcd.init().unwrap();
+    /// # }
     /// ```
     fn init(&mut self) -> Result<(), ()> {
         self.display_function = FOUR_BIT_MODE | ONE_LINE | FIVE_X8_DOTS;
@@ -387,7 +411,9 @@ impl<I2cType, DelayType> LcdTrait for Lcd<I2cType, DelayType>
     /// * `row` - row number
     /// # Example
     ///
-    /// ```
+    /// ```no_run
+    /// # use embedded_hal::blocking::{delay::DelayMs, i2c::Write};
+    /// # fn example<I2C, D>(i2c: I2C, delay: D) where I2C: Write, D: DelayMs<u16> {
     /// use device_drivers::i2c::lcd::{LcdTrait, Lcd};
     /// let mut lcd = Lcd::new(i2c, 0x27, delay).build();
     ///
@@ -396,6 +422,7 @@ impl<I2cType, DelayType> LcdTrait for Lcd<I2cType, DelayType>
     /// lcd.reset().unwrap();
     ///
     /// lcd.set_cursor(1, 8).unwrap();
+    /// # }
     /// ```
     fn set_cursor(&mut self, col: u8, mut row: u8) -> Result<(), ()> {
         const ROW_OFFSETS: [u8; 4] = [0x00_u8, 0x40_u8, 0x14_u8, 0x54_u8];
@@ -415,7 +442,9 @@ impl<I2cType, DelayType> LcdTrait for Lcd<I2cType, DelayType>
     ///
     /// # Example
     ///
-    /// ```
+    /// ```no_run
+    /// # use embedded_hal::blocking::{delay::DelayMs, i2c::Write};
+    /// # fn example<I2C, D>(i2c: I2C, delay: D) where I2C: Write, D: DelayMs<u16> {
     /// use device_drivers::i2c::lcd::{LcdTrait, Lcd};
     /// let mut lcd = Lcd::new(i2c, 0x27, delay).build();
     ///
@@ -424,6 +453,7 @@ impl<I2cType, DelayType> LcdTrait for Lcd<I2cType, DelayType>
     /// lcd.reset().unwrap();
     ///
     /// lcd.write_char('A').unwrap();
+    /// # }
     /// ```
     fn write_char(&mut self, data: char) -> Result<(), ()> {
         self.write_byte(data as u8)?;
@@ -439,7 +469,9 @@ impl<I2cType, DelayType> LcdTrait for Lcd<I2cType, DelayType>
     ///
     /// # Example
     ///
-    /// ```
+    /// ```no_run
+    /// # use embedded_hal::blocking::{delay::DelayMs, i2c::Write};
+    /// # fn example<I2C, D>(i2c: I2C, delay: D) where I2C: Write, D: DelayMs<u16> {
     /// use device_drivers::i2c::lcd::{LcdTrait, Lcd};
     /// let mut lcd = Lcd::new(i2c, 0x27, delay).build();
     ///
@@ -449,6 +481,7 @@ impl<I2cType, DelayType> LcdTrait for Lcd<I2cType, DelayType>
     ///
     /// lcd.write_bytes(&['A' as u8, 'B' as u8, 'C' as u8])
     ///     .unwrap();
+    /// # }
     /// ```
     fn write_bytes(&mut self, data: &[u8]) -> Result<(), ()> {
         for &b in data {
@@ -466,7 +499,9 @@ impl<I2cType, DelayType> LcdTrait for Lcd<I2cType, DelayType>
     ///
     /// # Example
     ///
-    /// ```
+    /// ```no_run
+    /// # use embedded_hal::blocking::{delay::DelayMs, i2c::Write};
+    /// # fn example<I2C, D>(i2c: I2C, delay: D) where I2C: Write, D: DelayMs<u16> {
     /// use device_drivers::i2c::lcd::{LcdTrait, Lcd};
     /// let mut lcd = Lcd::new(i2c, 0x27, delay).build();
     ///
@@ -476,6 +511,7 @@ impl<I2cType, DelayType> LcdTrait for Lcd<I2cType, DelayType>
     ///
     /// let string_to_write: &str = "Hello, World!";
     /// lcd.write_str(string_to_write).unwrap();
+    /// # }
     /// ```
     fn write_str(&mut self, data: &str) -> Result<(), ()> {
         self.write_bytes(data.as_bytes())?;
@@ -492,7 +528,9 @@ impl<I2cType, DelayType> LcdTrait for Lcd<I2cType, DelayType>
     ///
     /// # Example
     ///
-    /// ```
+    /// ```no_run
+    /// # use embedded_hal::blocking::{delay::DelayMs, i2c::Write};
+    /// # fn example<I2C, D>(i2c: I2C, delay: D) where I2C: Write, D: DelayMs<u16> {
     /// use device_drivers::i2c::lcd::{LcdTrait, Lcd};
     /// let mut lcd = Lcd::new(i2c, 0x27, delay).build();
     ///
@@ -500,7 +538,7 @@ impl<I2cType, DelayType> LcdTrait for Lcd<I2cType, DelayType>
     /// lcd.clear().unwrap();
     /// lcd.reset().unwrap();
     ///
-    /// let mut custom_symbol: [u8; 8] = [0; u8];
+    /// let mut custom_symbol: [u8; 8] = [0_u8; 8];
     ///
     /// custom_symbol[0] = 0b00111;
     /// custom_symbol[1] = 0b01111;
@@ -513,6 +551,7 @@ impl<I2cType, DelayType> LcdTrait for Lcd<I2cType, DelayType>
     ///
     /// lcd.create_char(0, &custom_symbol).unwrap();
     ///
+    /// # }
     /// ```
     fn create_char(&mut self, mut location: u8, char_map: &[u8; 8]) -> Result<(), ()> {
         location &= 0x07_u8;
@@ -537,6 +576,137 @@ impl<I2cType, DelayType> LcdTrait for Lcd<I2cType, DelayType>
     }
 }
 
+impl<I2cType, DelayType> TextDisplay for Lcd<I2cType, DelayType>
+    where
+        I2cType: Write,
+        DelayType: DelayMs<u16>
+{
+    type Error = ();
+
+    fn column_count(&self) -> u8 {
+        self.cols
+    }
+
+    fn row_count(&self) -> u8 {
+        self.rows
+    }
+
+    fn glyph_count(&self) -> u8 {
+        GLYPH_COUNT
+    }
+
+    fn clear_all(&mut self) -> Result<(), DisplayError<Self::Error>> {
+        LcdTrait::clear(self).map_err(DisplayError::Device)
+    }
+
+    fn put_char(&mut self, col: u8, row: u8, data: char) -> Result<(), DisplayError<Self::Error>> {
+        self.put_str(col, row, data.encode_utf8(&mut [0_u8; 4]))
+    }
+
+    /// Put string with one cursor move, chars out of ROM range are replaced by '?'
+    fn put_str(&mut self, col: u8, row: u8, data: &str) -> Result<(), DisplayError<Self::Error>> {
+        if col >= self.cols || row >= self.rows {
+            return Err(DisplayError::OutOfRange);
+        }
+
+        self.set_cursor(col, row).map_err(DisplayError::Device)?;
+
+        for ch in data.chars().take((self.cols - col) as usize) {
+            let byte = if (ch as u32) < 0x100 { ch as u8 } else { b'?' };
+            self.write_byte(byte).map_err(DisplayError::Device)?;
+        }
+
+        Ok(())
+    }
+
+    fn create_glyph(&mut self, location: u8, glyph: &[u8; 8]) -> Result<(), DisplayError<Self::Error>> {
+        if location >= GLYPH_COUNT { return Err(DisplayError::OutOfRange); }
+
+        self.create_char(location, glyph).map_err(DisplayError::Device)
+    }
+
+    fn put_glyph(&mut self, col: u8, row: u8, location: u8) -> Result<(), DisplayError<Self::Error>> {
+        if location >= GLYPH_COUNT { return Err(DisplayError::OutOfRange); }
+
+        self.put_char(col, row, char::from(location))
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use super::*;
+
+    /// Expander which records every i2c write
+    struct DumpExpander {
+        writes: Vec<Vec<u8>>
+    }
+
+    impl Write for DumpExpander {
+        type Error = ();
+
+        fn write(&mut self, address: u8, bytes: &[u8]) -> Result<(), ()> {
+            assert_eq!(address, 0x27);
+            self.writes.push(bytes.to_vec());
+            Ok(())
+        }
+    }
+
+    struct NoDelay;
+
+    impl DelayMs<u16> for NoDelay {
+        fn delay_ms(&mut self, _ms: u16) {}
+    }
+
+    fn lcd() -> Lcd<DumpExpander, NoDelay> {
+        Lcd::new(DumpExpander { writes: Vec::new() }, 0x27, NoDelay)
+            .columns(20)
+            .rows(4)
+            .build()
+    }
+
+    /// Decode bytes latched by enable pulses into (byte, is data)
+    fn sent(lcd: Lcd<DumpExpander, NoDelay>) -> Vec<(u8, bool)> {
+        let nibbles: Vec<u8> = lcd.i2c.writes.iter()
+            .filter(|bytes| bytes.len() == 2)
+            .map(|bytes| {
+                assert_eq!(bytes[1], bytes[0] | ENABLE);
+                assert_eq!(bytes[0] & BACKLIGHT, BACKLIGHT);
+                bytes[0]
+            })
+            .collect();
+
+        nibbles.chunks(2)
+            .map(|pair| {
+                assert_eq!(pair[0] & REGISTER_SELECT, pair[1] & REGISTER_SELECT);
+                ((pair[0] & 0xF0) | (pair[1] >> 4), pair[0] & REGISTER_SELECT != 0)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_write_str() {
+        let mut lcd = lcd();
+
+        lcd.write_str("Hi").unwrap();
+
+        assert_eq!(sent(lcd), vec![(b'H', true), (b'i', true)]);
+    }
+
+    #[test]
+    fn test_put_str() {
+        let mut lcd = lcd();
+
+        lcd.put_str(3, 2, "ab").unwrap();
+        lcd.put_char(0, 1, '\u{20AC}').unwrap();
+        lcd.put_str(18, 3, "xyz").unwrap();
+
+        assert_eq!(lcd.put_str(20, 0, "a"), Err(DisplayError::OutOfRange));
+        assert_eq!(lcd.put_char(0, 4, 'a'), Err(DisplayError::OutOfRange));
+
+        assert_eq!(sent(lcd), vec![
+            (SET_DRAM_ADDR | 0x17, false), (b'a', true), (b'b', true),
+            (SET_DRAM_ADDR | 0x40, false), (b'?', true),
+            (SET_DRAM_ADDR | 0x66, false), (b'x', true), (b'y', true)
+        ]);
+    }
 }
@@ -0,0 +1,104 @@
+use embedded_hal::blocking::i2c::{Read, Write};
+
+/// Address of MCP4017/MCP4018/MCP4019
+pub const DEFAULT_ADDRESS: u8 = 0x2F;
+
+/// Maximum wiper value (7 bit, 128 steps)
+pub const MAX_WIPER: u8 = 0x7F;
+
+#[derive(Debug)]
+pub enum Mcp401xError {
+    WrongValue,
+    I2cError
+}
+
+/// MCP4017/MCP4018 i2c digital potentiometer
+///
+/// # Example
+///
+/// ```
+/// # use embedded_hal::blocking::i2c::{Read, Write};
+/// # struct I2c;
+/// # impl Write for I2c {
+/// #     type Error = ();
+/// #     fn write(&mut self, _address: u8, _bytes: &[u8]) -> Result<(), ()> { Ok(()) }
+/// # }
+/// # impl Read for I2c {
+/// #     type Error = ();
+/// #     fn read(&mut self, _address: u8, _buffer: &mut [u8]) -> Result<(), ()> { Ok(()) }
+/// # }
+/// # let i2c = I2c;
+/// use device_drivers::i2c::mcp401x::{Mcp401x, DEFAULT_ADDRESS};
+///
+/// let mut pot = Mcp401x::new(i2c, DEFAULT_ADDRESS);
+/// pot.set_wiper(64).unwrap();
+/// ```
+pub struct Mcp401x<I2CType> {
+    i2c: I2CType,
+    address: u8
+}
+
+impl<I2CType> Mcp401x<I2CType>
+    where
+        I2CType: Read + Write
+{
+    pub fn new(i2c: I2CType, address: u8) -> Self {
+        Mcp401x {
+            i2c,
+            address
+        }
+    }
+
+    /// Release i2c
+    pub fn release(self) -> I2CType {
+        self.i2c
+    }
+
+    /// Write wiper value [0-127]
+    pub fn set_wiper(&mut self, value: u8) -> Result<(), Mcp401xError> {
+        if value > MAX_WIPER { return Err(Mcp401xError::WrongValue); }
+
+        self.i2c.write(self.address, &[value])
+            .map_err(|_| { Mcp401xError::I2cError })
+    }
+
+    /// Read wiper value [0-127]
+    pub fn get_wiper(&mut self) -> Result<u8, Mcp401xError> {
+        let mut data = [0_u8];
+
+        self.i2c.read(self.address, &mut data)
+            .map_err(|_| { Mcp401xError::I2cError })?;
+
+        Ok(data[0] & MAX_WIPER)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i2c::mock::{DumpI2c, FailingI2c};
+
+    #[test]
+    fn test_wiper() {
+        let mut pot = Mcp401x::new(DumpI2c::new(DEFAULT_ADDRESS, 1).pointer_size(0), DEFAULT_ADDRESS);
+
+        pot.set_wiper(100).unwrap();
+        assert_eq!(pot.get_wiper().unwrap(), 100);
+        assert!(matches!(pot.set_wiper(128), Err(Mcp401xError::WrongValue)));
+
+        let mut i2c = pot.release();
+        assert_eq!(i2c.registers[0], 100);
+
+        i2c.registers[0] = 0xFF;
+        let mut pot = Mcp401x::new(i2c, DEFAULT_ADDRESS);
+        assert_eq!(pot.get_wiper().unwrap(), MAX_WIPER);
+    }
+
+    #[test]
+    fn test_i2c_error() {
+        let mut pot = Mcp401x::new(FailingI2c, DEFAULT_ADDRESS);
+
+        assert!(matches!(pot.set_wiper(0), Err(Mcp401xError::I2cError)));
+        assert!(matches!(pot.get_wiper(), Err(Mcp401xError::I2cError)));
+    }
+}
@@ -0,0 +1,185 @@
+//! Mock i2c bus of register based device, used by tests of drivers
+//!
+//! Bytes after register pointer are written to consecutive registers, reads
+//! continue from register pointer. Behavior of particular chip (flags which
+//! can be cleared only, busy state, ...) is added by `Device` hooks.
+
+use embedded_hal::blocking::{delay::DelayUs, i2c::{Read, Write, WriteRead}};
+
+/// Hooks of mocked chip, default is plain memory
+pub(crate) trait Device {
+    /// Start of transaction with bytes written, error is NACK of address
+    fn start(&mut self, _registers: &mut [u8], _bytes: &[u8]) -> Result<(), ()> {
+        Ok(())
+    }
+
+    /// Write one byte to register
+    fn write(&mut self, registers: &mut [u8], pointer: usize, value: u8) {
+        registers[pointer] = value;
+    }
+
+    /// Read one byte from register
+    fn read(&mut self, registers: &mut [u8], pointer: usize) -> u8 {
+        registers[pointer]
+    }
+
+    /// End of transaction, `read` is set for `write_read`
+    fn stop(&mut self, _registers: &mut [u8], _bytes: &[u8], _read: bool) {}
+}
+
+impl Device for () {}
+
+pub(crate) struct DumpI2c<D = ()> {
+    pub address: u8,
+    pub registers: Vec<u8>,
+    pub pointer: usize,
+    /// Bytes of register pointer, big endian (2 for eeproms, 0 for chips
+    /// with single register)
+    pub pointer_size: usize,
+    pub device: D
+}
+
+impl DumpI2c {
+    pub fn new(address: u8, size: usize) -> Self {
+        DumpI2c::with_device(address, size, ())
+    }
+}
+
+impl<D> DumpI2c<D>
+    where
+        D: Device
+{
+    pub fn with_device(address: u8, size: usize, device: D) -> Self {
+        DumpI2c {
+            address,
+            registers: vec![0_u8; size],
+            pointer: 0,
+            pointer_size: 1,
+            device
+        }
+    }
+
+    pub fn pointer_size(mut self, size: usize) -> Self {
+        self.pointer_size = size;
+        self
+    }
+
+    fn start(&mut self, address: u8, bytes: &[u8]) -> Result<(), ()> {
+        assert_eq!(address, self.address);
+
+        self.device.start(&mut self.registers, bytes)?;
+
+        if bytes.len() >= self.pointer_size {
+            self.pointer = bytes[..self.pointer_size].iter()
+                .fold(0_usize, |pointer, &b| (pointer << 8) | b as usize);
+        }
+
+        Ok(())
+    }
+}
+
+impl<D> Write for DumpI2c<D>
+    where
+        D: Device
+{
+    type Error = ();
+
+    fn write(&mut self, address: u8, bytes: &[u8]) -> Result<(), ()> {
+        self.start(address, bytes)?;
+
+        for &b in bytes.iter().skip(self.pointer_size) {
+            self.device.write(&mut self.registers, self.pointer, b);
+            self.pointer += 1;
+        }
+
+        self.device.stop(&mut self.registers, bytes, false);
+
+        Ok(())
+    }
+}
+
+impl<D> WriteRead for DumpI2c<D>
+    where
+        D: Device
+{
+    type Error = ();
+
+    fn write_read(&mut self, address: u8, bytes: &[u8], buffer: &mut [u8]) -> Result<(), ()> {
+        self.start(address, bytes)?;
+
+        for b in buffer.iter_mut() {
+            *b = self.device.read(&mut self.registers, self.pointer);
+            self.pointer += 1;
+        }
+
+        self.device.stop(&mut self.registers, bytes, true);
+
+        Ok(())
+    }
+}
+
+/// Plain read continues from register pointer
+impl<D> Read for DumpI2c<D>
+    where
+        D: Device
+{
+    type Error = ();
+
+    fn read(&mut self, address: u8, buffer: &mut [u8]) -> Result<(), ()> {
+        assert_eq!(address, self.address);
+
+        self.device.start(&mut self.registers, &[])?;
+
+        if self.pointer_size == 0 {
+            self.pointer = 0;
+        }
+
+        for b in buffer.iter_mut() {
+            *b = self.device.read(&mut self.registers, self.pointer);
+            self.pointer += 1;
+        }
+
+        self.device.stop(&mut self.registers, &[], true);
+
+        Ok(())
+    }
+}
+
+/// Bus which fails every transaction with error 0x2A
+pub(crate) struct FailingI2c;
+
+impl Write for FailingI2c {
+    type Error = u8;
+
+    fn write(&mut self, _address: u8, _bytes: &[u8]) -> Result<(), u8> {
+        Err(0x2A)
+    }
+}
+
+impl Read for FailingI2c {
+    type Error = u8;
+
+    fn read(&mut self, _address: u8, _buffer: &mut [u8]) -> Result<(), u8> {
+        Err(0x2A)
+    }
+}
+
+impl WriteRead for FailingI2c {
+    type Error = u8;
+
+    fn write_read(&mut self, _address: u8, _bytes: &[u8], _buffer: &mut [u8]) -> Result<(), u8> {
+        Err(0x2A)
+    }
+}
+
+/// Delay which only sums requested time
+#[derive(Default)]
+pub(crate) struct MockDelay {
+    pub elapsed_us: u32
+}
+
+impl DelayUs<u16> for MockDelay {
+    fn delay_us(&mut self, us: u16) {
+        self.elapsed_us += us as u32;
+    }
+}
@@ -1,3 +1,10 @@
 
 pub mod lcd;
 pub mod rtc;
+pub mod mcp401x;
+pub mod shared;
+pub mod scanner;
+pub mod at24cx;
+
+#[cfg(test)]
+pub(crate) mod mock;
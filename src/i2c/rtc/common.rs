@@ -0,0 +1,176 @@
+//! Helpers shared by rtc drivers: BCD conversion, hour encoding and access
+//! to registers addressed by one byte register pointer
+
+use embedded_hal::blocking::i2c::{Write, WriteRead};
+
+/// Mode of hours in time and alarm registers
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HourMode {
+    _24Hour,
+    _12Hour
+}
+
+/// Value out of range, converted to `WrongValue` error of driver
+#[derive(Debug, PartialEq)]
+pub(crate) struct WrongValue;
+
+/// PM bit of hours register in 12 hour mode, same on all supported chips
+const HOUR_PM: u8 = 0b0010_0000;
+
+pub(crate) fn bcd_to_bin(value: u8) -> u8 {
+    value - 6 * (value >> 4)
+}
+
+pub(crate) fn bin_to_bcd(value: u8) -> u8 {
+    value + 6 * (value / 10)
+}
+
+/// Convert to BCD, value must be in range [`min`-`max`]
+pub(crate) fn check(value: u8, min: u8, max: u8) -> Result<u8, WrongValue> {
+    if value < min || value > max { return Err(WrongValue); }
+
+    Ok(bin_to_bcd(value))
+}
+
+/// Decode hours register [0-23], `hour_12` tells if it holds 12 hour value
+pub(crate) fn decode_hours(value: u8, hour_12: bool) -> u8 {
+    if !hour_12 {
+        return bcd_to_bin(value & 0x3F);
+    }
+
+    let hours = bcd_to_bin(value & 0x1F) % 12;
+
+    match value & HOUR_PM {
+        0 => hours,
+        _ => hours + 12
+    }
+}
+
+/// Encode hours [0-23] in `mode`, `hour_12` is flag of 12 hour mode kept
+/// in hours register (0 if chip keeps the mode in control register)
+pub(crate) fn encode_hours(hours: u8, mode: HourMode, hour_12: u8) -> Result<u8, WrongValue> {
+    if hours > 23 { return Err(WrongValue); }
+
+    match mode {
+        HourMode::_24Hour => Ok(bin_to_bcd(hours)),
+        HourMode::_12Hour => {
+            let pm = if hours >= 12 { HOUR_PM } else { 0_u8 };
+            let hours = match hours % 12 {
+                0 => 12,
+                hours => hours
+            };
+
+            Ok(hour_12 | pm | bin_to_bcd(hours))
+        }
+    }
+}
+
+/// Registers of chip addressed by one byte register pointer
+pub(crate) trait Registers<E> {
+    type I2c: Write<Error = E> + WriteRead<Error = E>;
+    type Error;
+
+    /// Bus and address of chip
+    fn bus(&mut self) -> (&mut Self::I2c, u8);
+
+    /// Wrap error of bus into error of driver
+    fn bus_error(error: E) -> Self::Error;
+
+    fn write(&mut self, data: &[u8]) -> Result<(), Self::Error> {
+        let (i2c, address) = self.bus();
+
+        i2c.write(address, data)
+            .map_err(Self::bus_error)
+    }
+
+    /// Read registers in one transaction with repeated start, so register
+    /// pointer can not be moved by other master on shared bus
+    fn read_registers(&mut self, register: u8, data: &mut [u8]) -> Result<(), Self::Error> {
+        let (i2c, address) = self.bus();
+
+        i2c.write_read(address, &[register], data)
+            .map_err(Self::bus_error)
+    }
+
+    fn read_register(&mut self, register: u8) -> Result<u8, Self::Error> {
+        let mut data = [0_u8];
+
+        self.read_registers(register, &mut data)?;
+
+        Ok(data[0])
+    }
+
+    fn write_register(&mut self, register: u8, value: u8) -> Result<(), Self::Error> {
+        self.write(&[register, value])
+    }
+
+    /// Write `value` to bits of register selected by `mask`
+    fn update_register(&mut self, register: u8, mask: u8, value: u8) -> Result<(), Self::Error> {
+        let current = self.read_register(register)?;
+
+        self.write_register(register, (current & !mask) | (value & mask))
+    }
+}
+
+/// Implement `Registers` and conversions from `WrongValue` and `DateTimeErrors`
+/// for driver `Rtc<I2CType, ...>` with error `RtcError<E>` having `WrongValue`
+/// and `I2cError` variants, arguments are other type parameters of driver
+macro_rules! impl_registers {
+    ($($param:ident),*) => {
+        impl<I2CType, E $(, $param)*> $crate::i2c::rtc::common::Registers<E> for Rtc<I2CType $(, $param)*>
+            where
+                I2CType: Write<Error = E> + WriteRead<Error = E>
+        {
+            type I2c = I2CType;
+            type Error = RtcError<E>;
+
+            fn bus(&mut self) -> (&mut I2CType, u8) {
+                (&mut self.i2c, self.address)
+            }
+
+            fn bus_error(error: E) -> RtcError<E> {
+                RtcError::I2cError(error)
+            }
+        }
+
+        impl<E> From<$crate::i2c::rtc::common::WrongValue> for RtcError<E> {
+            fn from(_: $crate::i2c::rtc::common::WrongValue) -> Self {
+                RtcError::WrongValue
+            }
+        }
+
+        impl<E> From<$crate::i2c::rtc::datetime::DateTimeErrors> for RtcError<E> {
+            fn from(_: $crate::i2c::rtc::datetime::DateTimeErrors) -> Self {
+                RtcError::WrongValue
+            }
+        }
+    };
+}
+
+pub(crate) use impl_registers;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bcd() {
+        assert_eq!(bin_to_bcd(59), 0x59);
+        assert_eq!(bcd_to_bin(0x59), 59);
+        assert_eq!(check(32, 1, 31), Err(WrongValue));
+        assert_eq!(check(31, 1, 31), Ok(0x31));
+    }
+
+    #[test]
+    fn test_hours() {
+        assert_eq!(encode_hours(0, HourMode::_12Hour, 0x40), Ok(0x52));
+        assert_eq!(encode_hours(13, HourMode::_12Hour, 0), Ok(0x21));
+        assert_eq!(encode_hours(23, HourMode::_24Hour, 0x40), Ok(0x23));
+        assert_eq!(encode_hours(24, HourMode::_24Hour, 0), Err(WrongValue));
+
+        assert_eq!(decode_hours(0x52, true), 0);
+        assert_eq!(decode_hours(0x72, true), 12);
+        assert_eq!(decode_hours(0x71, true), 23);
+        assert_eq!(decode_hours(0x23, false), 23);
+    }
+}
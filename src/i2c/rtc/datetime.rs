@@ -1,13 +1,80 @@
+use core::ops::{Add, Sub};
 use super::traits::DateTimeTrait;
+use super::duration::Duration;
 
-#[derive(Clone, Copy)]
+mod format;
+#[cfg(any(feature = "chrono", feature = "time"))]
+mod convert;
+
+/// Day of week
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Weekday {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday
+}
+
+const WEEKDAYS: [Weekday; 7] = [
+    Weekday::Monday,
+    Weekday::Tuesday,
+    Weekday::Wednesday,
+    Weekday::Thursday,
+    Weekday::Friday,
+    Weekday::Saturday,
+    Weekday::Sunday
+];
+
+impl Weekday {
+    /// Calculate weekday of date in Gregorian calendar
+    pub fn from_date(year: u16, month: u8, day: u8) -> Self {
+        const MONTH_OFFSETS: [i32; 12] = [0, 3, 2, 5, 0, 3, 5, 1, 4, 6, 2, 4];
+
+        // i32, sum does not fit u16 for large years and year 0 goes negative
+        let year = if month < 3 { year as i32 - 1 } else { year as i32 };
+        let month = (month.clamp(1, 12) - 1) as usize;
+
+        // 0 - Sunday
+        let weekday = (year + year.div_euclid(4) - year.div_euclid(100) + year.div_euclid(400)
+            + MONTH_OFFSETS[month] + day as i32).rem_euclid(7);
+
+        WEEKDAYS[((weekday + 6) % 7) as usize]
+    }
+
+    /// Number [1-7] of weekday in week which starts from `first`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use device_drivers::i2c::rtc::datetime::Weekday;
+    /// assert_eq!(Weekday::Monday.number_from(Weekday::Sunday), 2);
+    /// ```
+    pub fn number_from(self, first: Weekday) -> u8 {
+        (self as u8 + 7 - first as u8) % 7 + 1
+    }
+
+    /// Weekday by number [1-7] in week which starts from `first`
+    pub fn from_number(number: u8, first: Weekday) -> Option<Self> {
+        if number == 0 || number > 7 { return None; }
+
+        Some(WEEKDAYS[((first as u8 + number - 1) % 7) as usize])
+    }
+}
+
+/// Date and time, ordered chronologically
+///
+/// Fields are declared from most significant, derived ordering relies on it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct DateTime {
-    seconds: u8,
-    minutes: u8,
-    hours: u8,
-    day: u8,
+    year: u16,
     month: u8,
-    year: u16
+    day: u8,
+    hours: u8,
+    minutes: u8,
+    seconds: u8
 }
 
 impl DateTime {
@@ -22,42 +89,236 @@ impl DateTime {
         }
     }
 
-    pub fn seconds(mut self, seconds: u8) -> Self {
-        self.seconds = seconds;
-        self
+    /// Add duration, `None` if result is before 1970 or after year 65535
+    pub fn checked_add(&self, duration: Duration) -> Option<Self> {
+        let time = self.to_unix_time().checked_add(duration.as_seconds())?;
+
+        DateTime::from_unix_time(time).ok()
     }
 
-    pub fn minutes(mut self, minutes: u8) -> Self {
-        self.minutes = minutes;
-        self
+    /// Subtract duration, `None` if result is before 1970 or after year 65535
+    pub fn checked_sub(&self, duration: Duration) -> Option<Self> {
+        self.checked_add(-duration)
     }
 
-    pub fn hours(mut self, hours: u8) -> Self {
-        self.hours = hours;
-        self
+    /// Return checked date and time
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use device_drivers::i2c::rtc::datetime::{DateTime, DateTimeErrors};
+    /// let datetime = DateTime::from_ymd_hms(2021, 3, 14, 15, 9, 26).unwrap();
+    /// assert!(matches!(DateTime::from_ymd_hms(2021, 2, 29, 0, 0, 0), Err(DateTimeErrors::WrongDay)));
+    /// ```
+    pub fn from_ymd_hms(
+        year: u16,
+        month: u8,
+        day: u8,
+        hours: u8,
+        minutes: u8,
+        seconds: u8
+    ) -> Result<Self, DateTimeErrors> {
+        DateTime::new()
+            .year(year)?
+            .month(month)?
+            .day(day)?
+            .hours(hours)?
+            .minutes(minutes)?
+            .seconds(seconds)
     }
 
-    pub fn day(mut self, day: u8) -> Self {
-        self.day = day;
-        self
+    /// Return date and time from count of days since 1970-01-01
+    pub fn from_days_since_epoch(days: u32) -> Result<Self, DateTimeErrors> {
+        let (year, month, day) = Self::civil_from_days(days);
+
+        if year > u16::MAX as i64 { return Err(DateTimeErrors::WrongYear); }
+
+        DateTime::new()
+            .year(year as u16)?
+            .month(month)?
+            .day(day)
     }
 
-    pub fn month(mut self, month: u8) -> Self {
-        self.month = month;
-        self
+    /// Year, month and day of count of days since 1970-01-01
+    fn civil_from_days(days: u32) -> (i64, u8, u8) {
+        // Days to civil date, era is 400 years starting from March 1
+        let days = days as i64 + 719_468;
+        let era = days / 146_097;
+        let day_of_era = days - era * 146_097;
+        let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let month_index = (5 * day_of_year + 2) / 153;
+
+        let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u8;
+        let month = if month_index < 10 { month_index + 3 } else { month_index - 9 } as u8;
+        let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+        (year, month, day)
     }
 
-    pub fn year(mut self, year: u16) -> Self {
-        self.year = year;
-        self
+    /// Count of days since 1970-01-01
+    pub fn days_since_epoch(&self) -> u32 {
+        // Civil date to days, era is 400 years starting from March 1
+        let year = self.year as i64 - if self.month <= 2 { 1 } else { 0 };
+        let era = year / 400;
+        let year_of_era = year - era * 400;
+        let month = self.month as i64;
+        let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + self.day as i64 - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+        (era * 146_097 + day_of_era - 719_468) as u32
+    }
+
+    /// Return date and time from Unix time (seconds since 1970-01-01 00:00:00)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use device_drivers::i2c::rtc::datetime::DateTime;
+    /// let datetime = DateTime::from_unix_time(1_615_734_566).unwrap();
+    /// assert_eq!(datetime.to_unix_time(), 1_615_734_566);
+    /// ```
+    pub fn from_unix_time(time: i64) -> Result<Self, DateTimeErrors> {
+        if time < 0 { return Err(DateTimeErrors::WrongYear); }
+
+        let days = time / SECONDS_IN_DAY;
+        let seconds = time % SECONDS_IN_DAY;
+
+        if days > u32::MAX as i64 { return Err(DateTimeErrors::WrongYear); }
+
+        DateTime::from_days_since_epoch(days as u32)?
+            .hours((seconds / 3600) as u8)?
+            .minutes((seconds % 3600 / 60) as u8)?
+            .seconds((seconds % 60) as u8)
+    }
+
+    /// Return date and time from 32 bit Unix time, covers up to 2106-02-07
+    pub fn from_unix_time_u32(time: u32) -> Self {
+        let seconds = time % SECONDS_IN_DAY as u32;
+        // year is at most 2106, it always fits
+        let (year, month, day) = Self::civil_from_days(time / SECONDS_IN_DAY as u32);
+
+        DateTime {
+            year: year as u16,
+            month,
+            day,
+            hours: (seconds / 3600) as u8,
+            minutes: (seconds % 3600 / 60) as u8,
+            seconds: (seconds % 60) as u8
+        }
+    }
+
+    /// Unix time (seconds since 1970-01-01 00:00:00)
+    pub fn to_unix_time(&self) -> i64 {
+        self.days_since_epoch() as i64 * SECONDS_IN_DAY
+            + self.hours as i64 * 3600
+            + self.minutes as i64 * 60
+            + self.seconds as i64
+    }
+
+    /// 32 bit Unix time, fails after 2106-02-07 06:28:15
+    pub fn to_unix_time_u32(&self) -> Result<u32, DateTimeErrors> {
+        let time = self.to_unix_time();
+
+        if time > u32::MAX as i64 { return Err(DateTimeErrors::WrongYear); }
+
+        Ok(time as u32)
+    }
+
+    pub fn seconds(mut self, seconds: u8) -> Result<Self, DateTimeErrors> {
+        self.set_seconds(seconds)?;
+        Ok(self)
+    }
+
+    pub fn minutes(mut self, minutes: u8) -> Result<Self, DateTimeErrors> {
+        self.set_minutes(minutes)?;
+        Ok(self)
+    }
+
+    pub fn hours(mut self, hours: u8) -> Result<Self, DateTimeErrors> {
+        self.set_hours(hours)?;
+        Ok(self)
+    }
+
+    /// Set day of month, checked against month and year set before
+    pub fn day(mut self, day: u8) -> Result<Self, DateTimeErrors> {
+        self.set_day(day)?;
+        Ok(self)
+    }
+
+    pub fn month(mut self, month: u8) -> Result<Self, DateTimeErrors> {
+        self.set_month(month)?;
+        Ok(self)
     }
+
+    pub fn year(mut self, year: u16) -> Result<Self, DateTimeErrors> {
+        self.set_year(year)?;
+        Ok(self)
+    }
+}
+
+const SECONDS_IN_DAY: i64 = 86_400;
+
+impl Default for DateTime {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Panics if result is out of range, see `DateTime::checked_add`
+impl Add<Duration> for DateTime {
+    type Output = DateTime;
+
+    fn add(self, duration: Duration) -> DateTime {
+        self.checked_add(duration).expect("DateTime out of range")
+    }
+}
+
+/// Panics if result is out of range, see `DateTime::checked_sub`
+impl Sub<Duration> for DateTime {
+    type Output = DateTime;
+
+    fn sub(self, duration: Duration) -> DateTime {
+        self.checked_sub(duration).expect("DateTime out of range")
+    }
+}
+
+/// Time from `other` to `self`, negative if `other` is later
+impl Sub<DateTime> for DateTime {
+    type Output = Duration;
+
+    fn sub(self, other: DateTime) -> Duration {
+        Duration::from_seconds(self.to_unix_time() - other.to_unix_time())
+    }
+}
+
+const DAYS_IN_MONTH: [u8; 12] = [31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
+
+/// Check leap year by Gregorian rules
+pub fn is_leap_year(year: u16) -> bool {
+    year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400))
 }
 
-#[allow(dead_code)]
-const DAYS_IN_MONTH: [u8; 12] = [31, 30, 31, 30, 31, 31, 30, 31, 30, 31, 31, 29];
+/// Count of days in month [1-12] of year, 0 for wrong month
+pub fn days_in_month(year: u16, month: u8) -> u8 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        1..=12 => DAYS_IN_MONTH[(month - 1) as usize],
+        _ => 0
+    }
+}
 
+/// Field of date and time which has wrong value
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum DateTimeErrors {
-    WrongValue
+    WrongSeconds,
+    WrongMinutes,
+    WrongHours,
+    WrongDay,
+    WrongMonth,
+    WrongYear,
+    /// Text can not be parsed
+    WrongFormat
 }
 
 impl DateTimeTrait for DateTime {
@@ -91,9 +352,14 @@ impl DateTimeTrait for DateTime {
         Ok(self.year)
     }
 
+    /// Read the day of week, calculated from the date.
+    fn get_weekday(&self) -> Result<Weekday, Self::Error> {
+        Ok(Weekday::from_date(self.year, self.month, self.day))
+    }
+
     /// Set the seconds [0-59].
     fn set_seconds(&mut self, seconds: u8) -> Result<(), Self::Error> {
-        if seconds > 59 { return Err(Self::Error::WrongValue); }
+        if seconds > 59 { return Err(Self::Error::WrongSeconds); }
 
         self.seconds = seconds;
         Ok(())
@@ -101,7 +367,7 @@ impl DateTimeTrait for DateTime {
 
     /// Set the minutes [0-59].
     fn set_minutes(&mut self, minutes: u8) -> Result<(), Self::Error> {
-        if minutes > 59 { return Err(Self::Error::WrongValue); }
+        if minutes > 59 { return Err(Self::Error::WrongMinutes); }
 
         self.minutes = minutes;
         Ok(())
@@ -109,15 +375,15 @@ impl DateTimeTrait for DateTime {
 
     /// Set the hours.
     fn set_hours(&mut self, hours: u8) -> Result<(), Self::Error> {
-        if hours > 23 { return Err(Self::Error::WrongValue); }
+        if hours > 23 { return Err(Self::Error::WrongHours); }
 
         self.hours = hours;
         Ok(())
     }
 
-    /// Set the day of month [1-31].
+    /// Set the day of month [1-28/29/30/31].
     fn set_day(&mut self, day: u8) -> Result<(), Self::Error> {
-        if day > 31 || day == 0 { return Err(Self::Error::WrongValue); }
+        if day > days_in_month(self.year, self.month) || day == 0 { return Err(Self::Error::WrongDay); }
 
         self.day = day;
         Ok(())
@@ -125,7 +391,8 @@ impl DateTimeTrait for DateTime {
 
     /// Set the month [1-12].
     fn set_month(&mut self, month: u8) -> Result<(), Self::Error> {
-        if month > 12 || month == 0 { return Err(Self::Error::WrongValue); }
+        if month > 12 || month == 0 { return Err(Self::Error::WrongMonth); }
+        if self.day > days_in_month(self.year, month) { return Err(Self::Error::WrongDay); }
 
         self.month = month;
         Ok(())
@@ -133,9 +400,134 @@ impl DateTimeTrait for DateTime {
 
     /// Set the year. (e.g. 2000)
     fn set_year(&mut self, year: u16) -> Result<(), Self::Error> {
-        if year < 1970 { return Err(Self::Error::WrongValue); }
+        if year < 1970 { return Err(Self::Error::WrongYear); }
+        if self.day > days_in_month(year, self.month) { return Err(Self::Error::WrongDay); }
 
         self.year = year;
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_weekday_from_date() {
+        assert_eq!(Weekday::from_date(1970, 1, 1), Weekday::Thursday);
+        assert_eq!(Weekday::from_date(2000, 2, 29), Weekday::Tuesday);
+        assert_eq!(Weekday::from_date(2021, 3, 14), Weekday::Sunday);
+        assert_eq!(Weekday::from_date(2100, 3, 1), Weekday::Monday);
+        assert_eq!(Weekday::from_date(0, 1, 1), Weekday::Saturday);
+        assert_eq!(Weekday::from_date(0, 2, 29), Weekday::Tuesday);
+        assert_eq!(Weekday::from_date(65535, 12, 31), Weekday::Tuesday);
+    }
+
+    #[test]
+    fn test_days_in_month() {
+        assert_eq!(days_in_month(2021, 2), 28);
+        assert_eq!(days_in_month(2024, 2), 29);
+        assert_eq!(days_in_month(2100, 2), 28);
+        assert_eq!(days_in_month(2000, 2), 29);
+        assert_eq!(days_in_month(2021, 12), 31);
+        assert_eq!(days_in_month(2021, 13), 0);
+    }
+
+    #[test]
+    fn test_checked_constructor() {
+        assert!(DateTime::from_ymd_hms(2024, 2, 29, 23, 59, 59).is_ok());
+        assert_eq!(DateTime::from_ymd_hms(2023, 2, 29, 0, 0, 0).err(), Some(DateTimeErrors::WrongDay));
+        assert_eq!(DateTime::from_ymd_hms(2023, 4, 31, 0, 0, 0).err(), Some(DateTimeErrors::WrongDay));
+        assert_eq!(DateTime::from_ymd_hms(2023, 13, 1, 0, 0, 0).err(), Some(DateTimeErrors::WrongMonth));
+        assert_eq!(DateTime::from_ymd_hms(1969, 1, 1, 0, 0, 0).err(), Some(DateTimeErrors::WrongYear));
+        assert_eq!(DateTime::from_ymd_hms(2023, 1, 1, 24, 0, 0).err(), Some(DateTimeErrors::WrongHours));
+        assert_eq!(DateTime::from_ymd_hms(2023, 1, 1, 0, 60, 0).err(), Some(DateTimeErrors::WrongMinutes));
+        assert_eq!(DateTime::from_ymd_hms(2023, 1, 1, 0, 0, 60).err(), Some(DateTimeErrors::WrongSeconds));
+    }
+
+    #[test]
+    fn test_setters_check_calendar() {
+        let mut datetime = DateTime::from_ymd_hms(2024, 2, 29, 0, 0, 0).unwrap();
+
+        assert_eq!(datetime.set_year(2023).err(), Some(DateTimeErrors::WrongDay));
+        assert!(datetime.set_year(2028).is_ok());
+
+        let mut datetime = DateTime::new().day(31).unwrap();
+        assert_eq!(datetime.set_month(4).err(), Some(DateTimeErrors::WrongDay));
+        assert_eq!(datetime.set_day(32).err(), Some(DateTimeErrors::WrongDay));
+    }
+
+    #[test]
+    fn test_unix_time() {
+        let datetime = DateTime::from_ymd_hms(2021, 3, 14, 15, 9, 26).unwrap();
+        assert_eq!(datetime.to_unix_time(), 1_615_734_566);
+        assert_eq!(datetime.days_since_epoch(), 18_700);
+
+        assert_eq!(DateTime::new().to_unix_time(), 0);
+        assert_eq!(DateTime::from_ymd_hms(2000, 2, 29, 0, 0, 0).unwrap().to_unix_time(), 951_782_400);
+
+        let last = DateTime::from_unix_time_u32(u32::MAX);
+        assert_eq!(last.get_year().ok(), Some(2106));
+        assert_eq!(last.get_month().ok(), Some(2));
+        assert_eq!(last.get_day().ok(), Some(7));
+        assert_eq!(last.to_unix_time_u32().ok(), Some(u32::MAX));
+        assert_eq!(DateTime::from_unix_time_u32(1_615_734_566), datetime);
+
+        let after = DateTime::from_unix_time(u32::MAX as i64 + 1).unwrap();
+        assert_eq!(after.to_unix_time_u32().err(), Some(DateTimeErrors::WrongYear));
+
+        assert_eq!(DateTime::from_unix_time(-1).err(), Some(DateTimeErrors::WrongYear));
+    }
+
+    #[test]
+    fn test_days_round_trip() {
+        for days in (0..200_000_u32).step_by(7) {
+            let datetime = DateTime::from_days_since_epoch(days).unwrap();
+            assert_eq!(datetime.days_since_epoch(), days);
+        }
+    }
+
+    #[test]
+    fn test_ordering() {
+        let earlier = DateTime::from_ymd_hms(2021, 3, 14, 15, 9, 26).unwrap();
+        let later = DateTime::from_ymd_hms(2021, 3, 15, 0, 0, 0).unwrap();
+
+        assert!(earlier < later);
+        assert_eq!(earlier.max(later), later);
+        assert_eq!(earlier, DateTime::from_unix_time(1_615_734_566).unwrap());
+    }
+
+    #[test]
+    fn test_add_duration() {
+        let datetime = DateTime::from_ymd_hms(2023, 12, 31, 23, 59, 30).unwrap();
+
+        assert_eq!(datetime + Duration::from_seconds(30), DateTime::from_ymd_hms(2024, 1, 1, 0, 0, 0).unwrap());
+        assert_eq!(datetime + Duration::from_days(60), DateTime::from_ymd_hms(2024, 2, 29, 23, 59, 30).unwrap());
+        assert_eq!(datetime - Duration::from_days(365), DateTime::from_ymd_hms(2022, 12, 31, 23, 59, 30).unwrap());
+
+        assert_eq!(DateTime::new().checked_sub(Duration::from_seconds(1)), None);
+    }
+
+    #[test]
+    fn test_difference() {
+        let calibration = DateTime::from_ymd_hms(2024, 2, 28, 12, 0, 0).unwrap();
+        let now = DateTime::from_ymd_hms(2024, 3, 1, 13, 30, 0).unwrap();
+
+        let elapsed = now - calibration;
+        assert_eq!(elapsed.as_days(), 2);
+        assert_eq!(elapsed.as_minutes(), 2 * 24 * 60 + 90);
+        assert_eq!((calibration - now).as_hours(), -49);
+    }
+
+    #[test]
+    fn test_weekday_number() {
+        assert_eq!(Weekday::Monday.number_from(Weekday::Monday), 1);
+        assert_eq!(Weekday::Sunday.number_from(Weekday::Monday), 7);
+        assert_eq!(Weekday::Sunday.number_from(Weekday::Sunday), 1);
+        assert_eq!(Weekday::Saturday.number_from(Weekday::Sunday), 7);
+
+        assert_eq!(Weekday::from_number(1, Weekday::Sunday), Some(Weekday::Sunday));
+        assert_eq!(Weekday::from_number(7, Weekday::Monday), Some(Weekday::Sunday));
+        assert_eq!(Weekday::from_number(0, Weekday::Monday), None);
+    }
+}
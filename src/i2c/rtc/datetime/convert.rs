@@ -0,0 +1,103 @@
+use core::convert::TryFrom;
+use super::{DateTime, DateTimeErrors};
+
+#[cfg(feature = "chrono")]
+use chrono::{Datelike, NaiveDate, NaiveDateTime, Timelike};
+
+/// Every `DateTime` is in range of `NaiveDateTime`
+#[cfg(feature = "chrono")]
+impl From<DateTime> for NaiveDateTime {
+    fn from(datetime: DateTime) -> Self {
+        NaiveDate::from_ymd_opt(datetime.year as i32, datetime.month as u32, datetime.day as u32)
+            .and_then(|date| {
+                date.and_hms_opt(datetime.hours as u32, datetime.minutes as u32, datetime.seconds as u32)
+            })
+            .expect("DateTime is valid NaiveDateTime")
+    }
+}
+
+/// Fails for years out of range [1970-65535], leap second is dropped
+#[cfg(feature = "chrono")]
+impl TryFrom<NaiveDateTime> for DateTime {
+    type Error = DateTimeErrors;
+
+    fn try_from(datetime: NaiveDateTime) -> Result<Self, Self::Error> {
+        let year = u16::try_from(datetime.year()).map_err(|_| { DateTimeErrors::WrongYear })?;
+
+        DateTime::from_ymd_hms(
+            year,
+            datetime.month() as u8,
+            datetime.day() as u8,
+            datetime.hour() as u8,
+            datetime.minute() as u8,
+            datetime.second() as u8
+        )
+    }
+}
+
+/// Fails for years after 9999
+#[cfg(feature = "time")]
+impl TryFrom<DateTime> for time::PrimitiveDateTime {
+    type Error = DateTimeErrors;
+
+    fn try_from(datetime: DateTime) -> Result<Self, Self::Error> {
+        let month = time::Month::try_from(datetime.month)
+            .map_err(|_| { DateTimeErrors::WrongMonth })?;
+        let date = time::Date::from_calendar_date(datetime.year as i32, month, datetime.day)
+            .map_err(|_| { DateTimeErrors::WrongYear })?;
+        let time = time::Time::from_hms(datetime.hours, datetime.minutes, datetime.seconds)
+            .map_err(|_| { DateTimeErrors::WrongHours })?;
+
+        Ok(time::PrimitiveDateTime::new(date, time))
+    }
+}
+
+/// Fails for years before 1970
+#[cfg(feature = "time")]
+impl TryFrom<time::PrimitiveDateTime> for DateTime {
+    type Error = DateTimeErrors;
+
+    fn try_from(datetime: time::PrimitiveDateTime) -> Result<Self, Self::Error> {
+        let year = u16::try_from(datetime.year()).map_err(|_| { DateTimeErrors::WrongYear })?;
+
+        DateTime::from_ymd_hms(
+            year,
+            u8::from(datetime.month()),
+            datetime.day(),
+            datetime.hour(),
+            datetime.minute(),
+            datetime.second()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_chrono() {
+        let datetime = DateTime::from_ymd_hms(2021, 3, 14, 15, 9, 26).unwrap();
+        let naive = NaiveDateTime::from(datetime);
+
+        assert_eq!(naive.and_utc().timestamp(), datetime.to_unix_time());
+        assert_eq!(DateTime::try_from(naive), Ok(datetime));
+
+        let before = NaiveDate::from_ymd_opt(1969, 12, 31).unwrap().and_hms_opt(0, 0, 0).unwrap();
+        assert_eq!(DateTime::try_from(before), Err(DateTimeErrors::WrongYear));
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn test_time() {
+        let datetime = DateTime::from_ymd_hms(2024, 2, 29, 23, 59, 59).unwrap();
+        let primitive = time::PrimitiveDateTime::try_from(datetime).unwrap();
+
+        assert_eq!(primitive.assume_utc().unix_timestamp(), datetime.to_unix_time());
+        assert_eq!(DateTime::try_from(primitive), Ok(datetime));
+
+        let far = DateTime::from_ymd_hms(10_000, 1, 1, 0, 0, 0).unwrap();
+        assert_eq!(time::PrimitiveDateTime::try_from(far).err(), Some(DateTimeErrors::WrongYear));
+    }
+}
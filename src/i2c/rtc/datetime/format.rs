@@ -0,0 +1,357 @@
+use core::fmt::{self, Write};
+use heapless::{String, consts::U19};
+use super::{DateTime, DateTimeErrors, Weekday};
+use crate::i2c::rtc::duration::Duration;
+
+const MONTH_NAMES: [&str; 12] = [
+    "January", "February", "March", "April", "May", "June",
+    "July", "August", "September", "October", "November", "December"
+];
+
+const WEEKDAY_NAMES: [&str; 7] = [
+    "Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday", "Sunday"
+];
+
+/// ISO 8601 format "YYYY-MM-DDTHH:MM:SS"
+impl fmt::Display for DateTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.format(f, "%FT%T")
+    }
+}
+
+impl DateTime {
+    /// Write date and time by strftime-like pattern
+    ///
+    /// Supported specifiers: `%Y` year, `%y` year of century, `%m` month,
+    /// `%d` day, `%e` space padded day, `%H` hours, `%I` 12-hour hours,
+    /// `%p` AM/PM, `%M` minutes, `%S` seconds, `%j` day of year, `%a`/`%A`
+    /// weekday name, `%b`/`%B` month name, `%u` weekday [1-7] from Monday,
+    /// `%s` Unix time, `%F` as `%Y-%m-%d`, `%T` as `%H:%M:%S`, `%%`.
+    /// Unknown specifier is an error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use device_drivers::i2c::rtc::datetime::DateTime;
+    /// let datetime = DateTime::from_ymd_hms(2021, 3, 14, 15, 9, 26).unwrap();
+    ///
+    /// let mut line: heapless::String<heapless::consts::U16> = heapless::String::new();
+    /// datetime.format(&mut line, "%d.%m.%Y").unwrap();
+    /// assert_eq!(line.as_str(), "14.03.2021");
+    /// ```
+    pub fn format<W: Write>(&self, out: &mut W, pattern: &str) -> fmt::Result {
+        let mut chars = pattern.chars();
+
+        while let Some(ch) = chars.next() {
+            if ch != '%' {
+                out.write_char(ch)?;
+                continue;
+            }
+
+            let weekday = Weekday::from_date(self.year, self.month, self.day);
+            let month_name = MONTH_NAMES[(self.month - 1) as usize];
+            let hours_12 = match self.hours % 12 {
+                0 => 12,
+                hours => hours
+            };
+
+            match chars.next().ok_or(fmt::Error)? {
+                'Y' => write!(out, "{:04}", self.year)?,
+                'y' => write!(out, "{:02}", self.year % 100)?,
+                'm' => write!(out, "{:02}", self.month)?,
+                'd' => write!(out, "{:02}", self.day)?,
+                'e' => write!(out, "{:2}", self.day)?,
+                'H' => write!(out, "{:02}", self.hours)?,
+                'I' => write!(out, "{:02}", hours_12)?,
+                'p' => out.write_str(if self.hours < 12 { "AM" } else { "PM" })?,
+                'M' => write!(out, "{:02}", self.minutes)?,
+                'S' => write!(out, "{:02}", self.seconds)?,
+                'j' => write!(out, "{:03}", self.day_of_year())?,
+                'a' => out.write_str(&WEEKDAY_NAMES[weekday as usize][..3])?,
+                'A' => out.write_str(WEEKDAY_NAMES[weekday as usize])?,
+                'b' => out.write_str(&month_name[..3])?,
+                'B' => out.write_str(month_name)?,
+                'u' => write!(out, "{}", weekday.number_from(Weekday::Monday))?,
+                's' => write!(out, "{}", self.to_unix_time())?,
+                'F' => self.format(out, "%Y-%m-%d")?,
+                'T' => self.format(out, "%H:%M:%S")?,
+                '%' => out.write_char('%')?,
+                _ => return Err(fmt::Error)
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Format date and time as ISO 8601 "YYYY-MM-DDTHH:MM:SS", years
+    /// after 9999 do not fit the format and are `WrongYear` error
+    pub fn to_iso_string(&self) -> Result<String<U19>, DateTimeErrors> {
+        if self.year > 9999 { return Err(DateTimeErrors::WrongYear); }
+
+        let mut result = String::new();
+
+        write!(result, "{}", self).map_err(|_| DateTimeErrors::WrongFormat)?;
+
+        Ok(result)
+    }
+
+    /// Day of year [1-366]
+    pub fn day_of_year(&self) -> u16 {
+        let first = DateTime {
+            year: self.year,
+            month: 1,
+            day: 1,
+            hours: 0,
+            minutes: 0,
+            seconds: 0
+        };
+
+        (self.days_since_epoch() - first.days_since_epoch() + 1) as u16
+    }
+
+    /// Parse ISO 8601 date and time
+    ///
+    /// Accepts `YYYY-MM-DD`, optionally followed by `T` or space and
+    /// `HH:MM[:SS[.fraction]]` with optional `Z` or `±HH[:MM]` offset.
+    /// Time with offset is converted to UTC, fraction is dropped.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use device_drivers::i2c::rtc::datetime::DateTime;
+    /// let datetime = DateTime::parse_iso("2021-03-14T17:09:26+02:00").unwrap();
+    /// assert_eq!(datetime, DateTime::from_ymd_hms(2021, 3, 14, 15, 9, 26).unwrap());
+    /// ```
+    pub fn parse_iso(data: &str) -> Result<Self, DateTimeErrors> {
+        let mut parser = Parser { data: data.as_bytes(), position: 0 };
+
+        let year = parser.number(4)? as u16;
+        parser.expect(b'-')?;
+        let month = parser.number(2)? as u8;
+        parser.expect(b'-')?;
+        let day = parser.number(2)? as u8;
+
+        let date = DateTime::new().year(year)?.month(month)?.day(day)?;
+
+        match parser.next() {
+            None => return Ok(date),
+            Some(b'T') | Some(b' ') => {},
+            Some(_) => return Err(DateTimeErrors::WrongFormat)
+        }
+
+        let hours = parser.number(2)? as u8;
+        parser.expect(b':')?;
+        let minutes = parser.number(2)? as u8;
+
+        let seconds = match parser.peek() {
+            Some(b':') => {
+                parser.next();
+                let seconds = parser.number(2)? as u8;
+
+                if parser.peek() == Some(b'.') || parser.peek() == Some(b',') {
+                    parser.next();
+                    parser.skip_digits()?;
+                }
+
+                seconds
+            },
+            _ => 0
+        };
+
+        let datetime = date.hours(hours)?.minutes(minutes)?.seconds(seconds)?;
+
+        let sign = match parser.next() {
+            None => return Ok(datetime),
+            Some(b'Z') => return parser.end().map(|_| datetime),
+            Some(b'+') => 1,
+            Some(b'-') => -1,
+            Some(_) => return Err(DateTimeErrors::WrongFormat)
+        };
+
+        let offset_hours = parser.number(2)?;
+        let offset_minutes = match parser.peek() {
+            Some(b':') => {
+                parser.next();
+                parser.number(2)?
+            },
+            Some(_) => parser.number(2)?,
+            None => 0
+        };
+
+        parser.end()?;
+
+        if offset_hours > 23 || offset_minutes > 59 { return Err(DateTimeErrors::WrongFormat); }
+
+        let offset = sign * (offset_hours * 3600 + offset_minutes * 60) as i64;
+
+        datetime.checked_sub(Duration::from_seconds(offset))
+            .ok_or(DateTimeErrors::WrongYear)
+    }
+
+    /// Parse compiler build timestamp, date like "Mar 14 2021" or
+    /// "Mar  4 2021" and time like "15:09:26" (C `__DATE__`/`__TIME__`)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use device_drivers::i2c::rtc::datetime::DateTime;
+    /// let build = DateTime::parse_build_timestamp("Mar  4 2021", "15:09:26").unwrap();
+    /// assert_eq!(build, DateTime::from_ymd_hms(2021, 3, 4, 15, 9, 26).unwrap());
+    /// ```
+    pub fn parse_build_timestamp(date: &str, time: &str) -> Result<Self, DateTimeErrors> {
+        let mut parser = Parser { data: date.as_bytes(), position: 0 };
+
+        let name = date.get(0..3).ok_or(DateTimeErrors::WrongFormat)?;
+        let month = MONTH_NAMES.iter()
+            .position(|month| &month[..3] == name)
+            .ok_or(DateTimeErrors::WrongFormat)? as u8 + 1;
+
+        parser.position = 3;
+        parser.expect(b' ')?;
+        if parser.peek() == Some(b' ') { parser.next(); }
+        let day = parser.digits(2)? as u8;
+        parser.expect(b' ')?;
+        let year = parser.number(4)? as u16;
+        parser.end()?;
+
+        let mut parser = Parser { data: time.as_bytes(), position: 0 };
+
+        let hours = parser.number(2)? as u8;
+        parser.expect(b':')?;
+        let minutes = parser.number(2)? as u8;
+        parser.expect(b':')?;
+        let seconds = parser.number(2)? as u8;
+        parser.end()?;
+
+        DateTime::from_ymd_hms(year, month, day, hours, minutes, seconds)
+    }
+}
+
+struct Parser<'a> {
+    data: &'a [u8],
+    position: usize
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<u8> {
+        self.data.get(self.position).copied()
+    }
+
+    fn next(&mut self) -> Option<u8> {
+        let byte = self.peek()?;
+        self.position += 1;
+        Some(byte)
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), DateTimeErrors> {
+        match self.next() {
+            Some(b) if b == byte => Ok(()),
+            _ => Err(DateTimeErrors::WrongFormat)
+        }
+    }
+
+    fn end(&self) -> Result<(), DateTimeErrors> {
+        match self.peek() {
+            None => Ok(()),
+            Some(_) => Err(DateTimeErrors::WrongFormat)
+        }
+    }
+
+    /// Parse exactly `count` digits
+    fn number(&mut self, count: usize) -> Result<u32, DateTimeErrors> {
+        let mut value = 0_u32;
+
+        for _ in 0..count {
+            match self.next() {
+                Some(digit) if digit.is_ascii_digit() => value = value * 10 + (digit - b'0') as u32,
+                _ => return Err(DateTimeErrors::WrongFormat)
+            }
+        }
+
+        Ok(value)
+    }
+
+    /// Parse one to `max` digits
+    fn digits(&mut self, max: usize) -> Result<u32, DateTimeErrors> {
+        let start = self.position;
+        let mut value = 0_u32;
+
+        while let Some(digit) = self.peek().filter(|b| b.is_ascii_digit() && self.position - start < max) {
+            value = value * 10 + (digit - b'0') as u32;
+            self.position += 1;
+        }
+
+        if self.position == start { return Err(DateTimeErrors::WrongFormat); }
+
+        Ok(value)
+    }
+
+    /// Skip one or more digits
+    fn skip_digits(&mut self) -> Result<(), DateTimeErrors> {
+        let start = self.position;
+
+        while self.peek().filter(|b| b.is_ascii_digit()).is_some() {
+            self.position += 1;
+        }
+
+        if self.position == start { return Err(DateTimeErrors::WrongFormat); }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use heapless::consts::U32;
+
+    fn format(datetime: &DateTime, pattern: &str) -> Result<String<U32>, fmt::Error> {
+        let mut result = String::new();
+        datetime.format(&mut result, pattern)?;
+        Ok(result)
+    }
+
+    fn datetime() -> DateTime {
+        DateTime::from_ymd_hms(2021, 3, 4, 15, 9, 26).unwrap()
+    }
+
+    #[test]
+    fn test_format() {
+        let datetime = datetime();
+
+        assert_eq!(datetime.to_iso_string().unwrap().as_str(), "2021-03-04T15:09:26");
+        assert_eq!(format(&datetime, "%H:%M").unwrap().as_str(), "15:09");
+        assert_eq!(format(&datetime, "%d.%m.%Y").unwrap().as_str(), "04.03.2021");
+        assert_eq!(format(&datetime, "%a %b %e %I%p %j %u %%").unwrap().as_str(), "Thu Mar  4 03PM 063 4 %");
+        assert_eq!(format(&datetime, "%s").unwrap().as_str(), "1614870566");
+        assert!(format(&datetime, "%Q").is_err());
+
+        let last = DateTime::from_ymd_hms(65535, 12, 31, 23, 59, 59).unwrap();
+        assert_eq!(last.to_iso_string().err(), Some(DateTimeErrors::WrongYear));
+        let iso_last = DateTime::from_ymd_hms(9999, 12, 31, 23, 59, 59).unwrap();
+        assert_eq!(iso_last.to_iso_string().unwrap().as_str(), "9999-12-31T23:59:59");
+        assert_eq!(format(&last, "%F %a %j").unwrap().as_str(), "65535-12-31 Tue 365");
+        assert_eq!(format(&last, "%A %u %s").unwrap().as_str(), "Tuesday 2 2005949145599");
+    }
+
+    #[test]
+    fn test_parse_iso() {
+        assert_eq!(DateTime::parse_iso("2021-03-04T15:09:26").unwrap(), datetime());
+        assert_eq!(DateTime::parse_iso("2021-03-04 15:09:26.123Z").unwrap(), datetime());
+        assert_eq!(DateTime::parse_iso("2021-03-04T10:09:26-0500").unwrap(), datetime());
+        assert_eq!(DateTime::parse_iso("2021-03-04").unwrap(), DateTime::from_ymd_hms(2021, 3, 4, 0, 0, 0).unwrap());
+
+        assert_eq!(DateTime::parse_iso("2021-02-29").err(), Some(DateTimeErrors::WrongDay));
+        assert_eq!(DateTime::parse_iso("2021-03-04T15:9").err(), Some(DateTimeErrors::WrongFormat));
+        assert_eq!(DateTime::parse_iso("2021-03-04T15:09:26X").err(), Some(DateTimeErrors::WrongFormat));
+    }
+
+    #[test]
+    fn test_parse_build_timestamp() {
+        assert_eq!(DateTime::parse_build_timestamp("Mar  4 2021", "15:09:26").unwrap(), datetime());
+        assert_eq!(DateTime::parse_build_timestamp("Mar 04 2021", "15:09:26").unwrap(), datetime());
+        assert_eq!(DateTime::parse_build_timestamp("Mrz  4 2021", "15:09:26").err(), Some(DateTimeErrors::WrongFormat));
+        assert_eq!(DateTime::parse_build_timestamp("Mar 257 2021", "15:09:26").err(), Some(DateTimeErrors::WrongFormat));
+        assert_eq!(DateTime::parse_build_timestamp("Mar 32 2021", "15:09:26").err(), Some(DateTimeErrors::WrongDay));
+    }
+}
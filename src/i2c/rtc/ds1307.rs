@@ -0,0 +1,343 @@
+use core::fmt::Debug;
+use embedded_hal::blocking::{i2c::{Write, WriteRead}};
+use super::traits::{RtcTrait, ErrorTrait, ErrorKind, SquareWaveTrait, NvramTrait};
+use super::datetime::{DateTime, Weekday};
+use crate::i2c::rtc::traits::DateTimeTrait;
+use super::common::{Registers, impl_registers, bcd_to_bin, bin_to_bcd, decode_hours, encode_hours};
+
+pub use super::common::HourMode;
+
+/// Address of DS1307
+pub const DEFAULT_ADDRESS: u8 = 0x68;
+
+/// Size of battery backed RAM in bytes
+pub const RAM_SIZE: usize = 56;
+
+#[repr(u8)]
+enum ControlData {
+    Seconds = 0x00,
+    Control = 0x07,
+    Ram = 0x08
+}
+
+const CLOCK_HALT: u8 = 0b1000_0000;
+const HOUR_12: u8 = 0b0100_0000;
+
+const OUTPUT_LEVEL: u8 = 0b1000_0000;
+const SQUARE_WAVE_ENABLE: u8 = 0b0001_0000;
+
+const MIN_YEAR: u16 = 2000;
+const MAX_YEAR: u16 = 2099;
+
+/// Frequency of SQW/OUT pin (RS1, RS0)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SquareWaveFrequency {
+    _1Hz,
+    _4096Hz,
+    _8192Hz,
+    _32768Hz
+}
+
+/// DS1307 real time clock with 56 bytes of battery backed RAM
+///
+/// # Example
+///
+/// ```no_run
+/// # use embedded_hal::blocking::i2c::{Write, WriteRead};
+/// # use device_drivers::i2c::rtc::datetime::DateTime;
+/// # fn example<I2C, E>(i2c: I2C, build_time: DateTime)
+/// #     where I2C: Write<Error = E> + WriteRead<Error = E>, E: core::fmt::Debug
+/// # {
+/// use device_drivers::i2c::rtc::ds1307::{Rtc, DEFAULT_ADDRESS};
+/// use device_drivers::i2c::rtc::traits::RtcTrait;
+///
+/// let mut rtc = Rtc::new(i2c, DEFAULT_ADDRESS);
+/// if !rtc.is_running().unwrap() {
+///     rtc.set(&build_time).unwrap();
+/// }
+///
+/// rtc.write_ram(0, &[0x42]).unwrap();
+/// # }
+/// ```
+pub struct Rtc<I2CType> {
+    i2c: I2CType,
+    address: u8,
+    hour_mode: HourMode,
+    first_weekday: Weekday
+}
+
+#[derive(Debug)]
+pub enum RtcError<E> {
+    WrongValue,
+    /// Error of i2c bus
+    I2cError(E),
+    /// Clock is halted (CH), time is invalid until next `set`
+    ClockHalted
+}
+
+impl<I2CType, E> Rtc<I2CType>
+    where
+        I2CType: Write<Error = E> + WriteRead<Error = E>
+{
+    pub fn new(i2c: I2CType, address: u8) -> Self {
+        Rtc {
+            i2c,
+            address,
+            hour_mode: HourMode::_24Hour,
+            first_weekday: Weekday::Monday
+        }
+    }
+
+    /// Set mode of hours written by `set`
+    ///
+    /// 12 hour flag is part of hours register, so `get` decodes either mode.
+    pub fn hour_mode(mut self, mode: HourMode) -> Self {
+        self.hour_mode = mode;
+        self
+    }
+
+    /// Set weekday stored as 1 in day register (Monday by default), DS1307
+    /// only counts it, weekday returned by `get` is calculated from the date
+    pub fn first_weekday(mut self, first: Weekday) -> Self {
+        self.first_weekday = first;
+        self
+    }
+
+    /// Check that clock is not halted (CH)
+    pub fn is_running(&mut self) -> Result<bool, RtcError<E>> {
+        Ok(self.read_register(ControlData::Seconds as u8)? & CLOCK_HALT == 0)
+    }
+
+    /// Stop oscillator (CH = 1), seconds are kept
+    pub fn halt(&mut self) -> Result<(), RtcError<E>> {
+        self.update_register(ControlData::Seconds as u8, CLOCK_HALT, CLOCK_HALT)
+    }
+
+    /// Start oscillator (CH = 0), seconds are kept
+    pub fn start(&mut self) -> Result<(), RtcError<E>> {
+        self.update_register(ControlData::Seconds as u8, CLOCK_HALT, 0_u8)
+    }
+
+    /// Output square wave on SQW/OUT pin (SQWE = 1)
+    pub fn set_square_wave(&mut self, frequency: SquareWaveFrequency) -> Result<(), RtcError<E>> {
+        let rate = match frequency {
+            SquareWaveFrequency::_1Hz => 0b00,
+            SquareWaveFrequency::_4096Hz => 0b01,
+            SquareWaveFrequency::_8192Hz => 0b10,
+            SquareWaveFrequency::_32768Hz => 0b11
+        };
+
+        self.write_register(ControlData::Control as u8, SQUARE_WAVE_ENABLE | rate)
+    }
+
+    /// Drive SQW/OUT pin with static level (SQWE = 0, OUT = level)
+    pub fn disable_square_wave(&mut self, level: bool) -> Result<(), RtcError<E>> {
+        let value = if level { OUTPUT_LEVEL } else { 0_u8 };
+
+        self.write_register(ControlData::Control as u8, value)
+    }
+
+    /// Read battery backed RAM
+    ///
+    /// # Arguments
+    ///
+    /// * `offset` - first byte of RAM [0-55]
+    /// * `data` - buffer, must fit in RAM from offset
+    pub fn read_ram(&mut self, offset: u8, data: &mut [u8]) -> Result<(), RtcError<E>> {
+        if offset as usize + data.len() > RAM_SIZE { return Err(RtcError::WrongValue); }
+
+        self.read_registers(ControlData::Ram as u8 + offset, data)
+    }
+
+    /// Write battery backed RAM
+    ///
+    /// # Arguments
+    ///
+    /// * `offset` - first byte of RAM [0-55]
+    /// * `data` - bytes to write, must fit in RAM from offset
+    pub fn write_ram(&mut self, offset: u8, data: &[u8]) -> Result<(), RtcError<E>> {
+        if offset as usize + data.len() > RAM_SIZE { return Err(RtcError::WrongValue); }
+
+        let mut buffer = [0_u8; RAM_SIZE + 1];
+        buffer[0] = ControlData::Ram as u8 + offset;
+        buffer[1..=data.len()].copy_from_slice(data);
+
+        self.write(&buffer[..=data.len()])
+    }
+}
+
+impl_registers!();
+
+impl<E> ErrorTrait for RtcError<E>
+    where
+        E: Debug
+{
+    fn kind(&self) -> ErrorKind {
+        match self {
+            RtcError::WrongValue => ErrorKind::WrongValue,
+            RtcError::I2cError(_) => ErrorKind::Bus,
+            RtcError::ClockHalted => ErrorKind::TimeInvalid
+        }
+    }
+}
+
+impl<I2CType, E> RtcTrait<DateTime> for Rtc<I2CType>
+    where
+        I2CType: Write<Error = E> + WriteRead<Error = E>,
+        E: Debug
+{
+    type Error = RtcError<E>;
+
+    /// Write time and start clock, years [2000-2099] are supported
+    fn set(&mut self, datetime: &DateTime) -> Result<(), Self::Error> {
+        let year = datetime.get_year()?;
+
+        if !(MIN_YEAR..=MAX_YEAR).contains(&year) { return Err(RtcError::WrongValue); }
+
+        let data_to_send = [
+            ControlData::Seconds as u8,
+            bin_to_bcd(datetime.get_seconds()?),
+            bin_to_bcd(datetime.get_minutes()?),
+            encode_hours(datetime.get_hours()?, self.hour_mode, HOUR_12)?,
+            datetime.get_weekday()?.number_from(self.first_weekday),
+            bin_to_bcd(datetime.get_day()?),
+            bin_to_bcd(datetime.get_month()?),
+            bin_to_bcd((year - MIN_YEAR) as u8)
+        ];
+
+        self.write(&data_to_send)
+    }
+
+    /// Read time, fails with `ClockHalted` if clock is stopped
+    fn get(&mut self) -> Result<DateTime, Self::Error> {
+        let mut data_to_read = [0_u8; 7];
+
+        self.read_registers(ControlData::Seconds as u8, &mut data_to_read)?;
+
+        if data_to_read[0] & CLOCK_HALT != 0 {
+            return Err(RtcError::ClockHalted);
+        }
+
+        Ok(
+            DateTime::from_ymd_hms(
+                MIN_YEAR + (bcd_to_bin(data_to_read[6]) as u16),
+                bcd_to_bin(data_to_read[5] & 0x1F),
+                bcd_to_bin(data_to_read[4] & 0x3F),
+                decode_hours(data_to_read[2] & 0x7F, data_to_read[2] & HOUR_12 != 0),
+                bcd_to_bin(data_to_read[1] & 0x7F),
+                bcd_to_bin(data_to_read[0] & 0x7F)
+            )?
+        )
+    }
+}
+
+impl<I2CType, E> SquareWaveTrait for Rtc<I2CType>
+    where
+        I2CType: Write<Error = E> + WriteRead<Error = E>,
+        E: Debug
+{
+    type Frequency = SquareWaveFrequency;
+
+    fn set_square_wave(&mut self, frequency: SquareWaveFrequency) -> Result<(), Self::Error> {
+        Rtc::set_square_wave(self, frequency)
+    }
+
+    fn disable_square_wave(&mut self) -> Result<(), Self::Error> {
+        Rtc::disable_square_wave(self, false)
+    }
+}
+
+impl<I2CType, E> NvramTrait for Rtc<I2CType>
+    where
+        I2CType: Write<Error = E> + WriteRead<Error = E>,
+        E: Debug
+{
+    fn nvram_size(&self) -> usize {
+        RAM_SIZE
+    }
+
+    fn read_nvram(&mut self, offset: u8, data: &mut [u8]) -> Result<(), Self::Error> {
+        self.read_ram(offset, data)
+    }
+
+    fn write_nvram(&mut self, offset: u8, data: &[u8]) -> Result<(), Self::Error> {
+        self.write_ram(offset, data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i2c::mock::DumpI2c;
+
+    fn rtc() -> Rtc<DumpI2c> {
+        let mut i2c = DumpI2c::new(DEFAULT_ADDRESS, 0x40);
+        i2c.registers[0] = CLOCK_HALT;
+        i2c.registers[4] = 0x01;
+        i2c.registers[5] = 0x01;
+
+        Rtc::new(i2c, DEFAULT_ADDRESS)
+    }
+
+    #[test]
+    fn test_clock_halt() {
+        let mut rtc = rtc();
+
+        assert!(!rtc.is_running().unwrap());
+        assert!(matches!(rtc.get(), Err(RtcError::ClockHalted)));
+
+        let datetime = DateTime::from_ymd_hms(2021, 3, 14, 15, 9, 26).unwrap();
+        rtc.set(&datetime).unwrap();
+        assert_eq!(rtc.i2c.registers[0..7], [0x26, 0x09, 0x15, 0x07, 0x14, 0x03, 0x21]);
+        assert_eq!(rtc.get().unwrap(), datetime);
+
+        rtc.halt().unwrap();
+        assert_eq!(rtc.i2c.registers[0], 0xA6);
+        rtc.start().unwrap();
+        assert!(rtc.is_running().unwrap());
+
+        assert!(rtc.set(&DateTime::from_ymd_hms(2100, 1, 1, 0, 0, 0).unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_ram() {
+        let mut rtc = rtc();
+
+        rtc.write_ram(54, &[0xAA, 0x55]).unwrap();
+        assert_eq!(rtc.i2c.registers[0x3E..0x40], [0xAA, 0x55]);
+
+        let mut data = [0_u8; 2];
+        rtc.read_ram(54, &mut data).unwrap();
+        assert_eq!(data, [0xAA, 0x55]);
+
+        assert!(rtc.write_ram(55, &[0, 0]).is_err());
+        assert!(rtc.read_ram(0, &mut [0_u8; RAM_SIZE]).is_ok());
+    }
+
+    #[test]
+    fn test_square_wave() {
+        let mut rtc = rtc();
+
+        rtc.set_square_wave(SquareWaveFrequency::_32768Hz).unwrap();
+        assert_eq!(rtc.i2c.registers[0x07], 0x13);
+
+        rtc.disable_square_wave(true).unwrap();
+        assert_eq!(rtc.i2c.registers[0x07], 0x80);
+    }
+
+    #[test]
+    fn test_traits() {
+        fn backup<R: NvramTrait>(rtc: &mut R, data: &[u8]) -> Result<(), R::Error> {
+            rtc.write_nvram((rtc.nvram_size() - data.len()) as u8, data)
+        }
+
+        let mut rtc = rtc();
+        backup(&mut rtc, &[1, 2]).unwrap();
+        assert_eq!(rtc.i2c.registers[0x3E..0x40], [1, 2]);
+
+        SquareWaveTrait::disable_square_wave(&mut rtc).unwrap();
+        assert_eq!(rtc.i2c.registers[0x07], 0x00);
+
+        assert_eq!(rtc.get().unwrap_err().kind(), ErrorKind::TimeInvalid);
+    }
+}
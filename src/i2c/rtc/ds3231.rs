@@ -1,9 +1,13 @@
 
-use embedded_hal::blocking::{i2c::{Read, Write}};
-use super::traits::RtcTrait;
-use super::datetime::DateTime;
+use core::fmt::Debug;
+use embedded_hal::blocking::{i2c::{Write, WriteRead}};
+use super::traits::{RtcTrait, ErrorTrait, ErrorKind, AlarmTrait, TemperatureTrait, SquareWaveTrait, CalibrationTrait};
+use super::datetime::{DateTime, Weekday};
 use crate::i2c::rtc::traits::DateTimeTrait;
-use crate::i2c::rtc::datetime::DateTimeErrors;
+use super::common::{Registers, impl_registers, bcd_to_bin, bin_to_bcd, check, decode_hours, encode_hours};
+use core::convert::TryFrom;
+
+pub use super::common::HourMode;
 
 #[allow(dead_code)]
 #[repr(u8)]
@@ -12,98 +16,1205 @@ enum ControlData {
     StatusReg = 0x0F
 }
 
+#[repr(u8)]
+enum CalibrationData {
+    AgingOffset = 0x10
+}
+
+#[repr(u8)]
+enum TemperatureData {
+    Msb = 0x11
+}
+
+#[repr(u8)]
+enum AlarmData {
+    Alarm1 = 0x07,
+    Alarm2 = 0x0B
+}
+
+const ALARM1_INTERRUPT_ENABLE: u8 = 0b0000_0001;
+const ALARM2_INTERRUPT_ENABLE: u8 = 0b0000_0010;
+const INTERRUPT_CONTROL: u8 = 0b0000_0100;
+
+const ENABLE_OSCILLATOR: u8 = 0b1000_0000;
+const BATTERY_SQUARE_WAVE: u8 = 0b0100_0000;
+const CONVERT_TEMPERATURE: u8 = 0b0010_0000;
+const RATE_SELECT: u8 = 0b0001_1000;
+
+const OSCILLATOR_STOP_FLAG: u8 = 0b1000_0000;
+const ENABLE_32KHZ: u8 = 0b0000_1000;
+const BUSY: u8 = 0b0000_0100;
+
+const ALARM1_FLAG: u8 = 0b0000_0001;
+const ALARM2_FLAG: u8 = 0b0000_0010;
+
+const HOUR_12: u8 = 0b0100_0000;
+#[cfg(feature = "rtcc")]
+const HOUR_PM: u8 = 0b0010_0000;
+const CENTURY: u8 = 0b1000_0000;
+
+const MIN_YEAR: u16 = 2000;
+const MAX_YEAR: u16 = 2199;
+
+const ALARM_MASK: u8 = 0b1000_0000;
+const ALARM_WEEKDAY: u8 = 0b0100_0000;
+
+/// Frequency of INT/SQW pin in square wave mode (RS2, RS1)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SquareWaveFrequency {
+    _1Hz,
+    _1024Hz,
+    _4096Hz,
+    _8192Hz
+}
+
+impl SquareWaveFrequency {
+    fn from_register(value: u8) -> Self {
+        match (value & RATE_SELECT) >> 3 {
+            0b00 => SquareWaveFrequency::_1Hz,
+            0b01 => SquareWaveFrequency::_1024Hz,
+            0b10 => SquareWaveFrequency::_4096Hz,
+            _ => SquareWaveFrequency::_8192Hz
+        }
+    }
+
+    fn to_register(self) -> u8 {
+        let rate = match self {
+            SquareWaveFrequency::_1Hz => 0b00,
+            SquareWaveFrequency::_1024Hz => 0b01,
+            SquareWaveFrequency::_4096Hz => 0b10,
+            SquareWaveFrequency::_8192Hz => 0b11
+        };
+
+        rate << 3
+    }
+}
+
+/// Control register (0x0E)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Control {
+    /// Oscillator runs on battery (inverted EOSC)
+    pub oscillator_on_battery: bool,
+    /// Square wave on battery (BBSQW)
+    pub battery_square_wave: bool,
+    /// Square wave frequency (RS2, RS1)
+    pub square_wave_frequency: SquareWaveFrequency,
+    /// INT/SQW pin is interrupt output, otherwise square wave (INTCN)
+    pub interrupt_control: bool,
+    /// Alarm 2 interrupt (A2IE)
+    pub alarm2_interrupt: bool,
+    /// Alarm 1 interrupt (A1IE)
+    pub alarm1_interrupt: bool
+}
+
+impl Control {
+    fn from_register(value: u8) -> Self {
+        Control {
+            oscillator_on_battery: value & ENABLE_OSCILLATOR == 0,
+            battery_square_wave: value & BATTERY_SQUARE_WAVE != 0,
+            square_wave_frequency: SquareWaveFrequency::from_register(value),
+            interrupt_control: value & INTERRUPT_CONTROL != 0,
+            alarm2_interrupt: value & ALARM2_INTERRUPT_ENABLE != 0,
+            alarm1_interrupt: value & ALARM1_INTERRUPT_ENABLE != 0
+        }
+    }
+
+    fn to_register(self) -> u8 {
+        let bit = |state: bool, bit: u8| if state { bit } else { 0 };
+
+        bit(!self.oscillator_on_battery, ENABLE_OSCILLATOR)
+            | bit(self.battery_square_wave, BATTERY_SQUARE_WAVE)
+            | self.square_wave_frequency.to_register()
+            | bit(self.interrupt_control, INTERRUPT_CONTROL)
+            | bit(self.alarm2_interrupt, ALARM2_INTERRUPT_ENABLE)
+            | bit(self.alarm1_interrupt, ALARM1_INTERRUPT_ENABLE)
+    }
+}
+
+/// Status register (0x0F)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Status {
+    /// Oscillator was stopped, time may be invalid (OSF)
+    pub oscillator_stopped: bool,
+    /// 32 kHz output is enabled (EN32kHz)
+    pub enable_32khz: bool,
+    /// Temperature conversion is in progress (BSY)
+    pub busy: bool,
+    /// Alarm 2 matched (A2F)
+    pub alarm2_matched: bool,
+    /// Alarm 1 matched (A1F)
+    pub alarm1_matched: bool
+}
+
+impl Status {
+    fn from_register(value: u8) -> Self {
+        Status {
+            oscillator_stopped: value & OSCILLATOR_STOP_FLAG != 0,
+            enable_32khz: value & ENABLE_32KHZ != 0,
+            busy: value & BUSY != 0,
+            alarm2_matched: value & ALARM2_FLAG != 0,
+            alarm1_matched: value & ALARM1_FLAG != 0
+        }
+    }
+}
+
+/// Time of reference clock and rtc read at the same moment, in seconds
+///
+/// Reference is any outside source (GPS, host, manual set), both values
+/// may use any epoch, only differences between samples are used.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DriftSample {
+    pub reference: u32,
+    pub rtc: u32
+}
+
+/// Drift of rtc between two samples in tenths of ppm, positive if rtc is fast
+///
+/// Rtc must not be set between samples. Returns `None` if reference time
+/// did not advance.
+pub fn drift(start: &DriftSample, end: &DriftSample) -> Option<i32> {
+    let reference_elapsed = end.reference as i64 - start.reference as i64;
+    let rtc_elapsed = end.rtc as i64 - start.rtc as i64;
+
+    if reference_elapsed <= 0 { return None; }
+
+    let error = (rtc_elapsed - reference_elapsed) * 10_000_000;
+    let rounding = reference_elapsed / 2 * error.signum();
+
+    Some(((error + rounding) / reference_elapsed) as i32)
+}
+
+/// Alarm 1 with match mode, fires once per second at most
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Alarm1 {
+    /// Every second
+    EverySecond,
+    /// Seconds match
+    Seconds { seconds: u8 },
+    /// Minutes and seconds match
+    Minutes { minutes: u8, seconds: u8 },
+    /// Hours, minutes and seconds match
+    Hours { hours: u8, minutes: u8, seconds: u8 },
+    /// Day of month [1-31], hours, minutes and seconds match
+    Date { date: u8, hours: u8, minutes: u8, seconds: u8 },
+    /// Day of week [1-7], hours, minutes and seconds match
+    Weekday { weekday: u8, hours: u8, minutes: u8, seconds: u8 }
+}
+
+/// Alarm 2 with match mode, fires at 00 seconds of minute
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Alarm2 {
+    /// Every minute
+    EveryMinute,
+    /// Minutes match
+    Minutes { minutes: u8 },
+    /// Hours and minutes match
+    Hours { hours: u8, minutes: u8 },
+    /// Day of month [1-31], hours and minutes match
+    Date { date: u8, hours: u8, minutes: u8 },
+    /// Day of week [1-7], hours and minutes match
+    Weekday { weekday: u8, hours: u8, minutes: u8 }
+}
+
 pub struct Rtc<I2CType> {
     i2c: I2CType,
-    address: u8
+    address: u8,
+    hour_mode: HourMode,
+    first_weekday: Weekday,
+    converting: bool
 }
 
-pub enum RtcError {
+#[derive(Debug)]
+pub enum RtcError<E> {
     WrongValue,
-    I2cError
+    /// Error of i2c bus
+    I2cError(E),
+    /// Oscillator was stopped (OSF), time is invalid until next `set`
+    OscillatorStopped
 }
 
-impl<I2CType> Rtc<I2CType>
+impl<I2CType, E> Rtc<I2CType>
     where
-        I2CType: Read + Write,
+        I2CType: Write<Error = E> + WriteRead<Error = E>,
 {
     pub fn new(i2c: I2CType, address: u8) -> Self {
         Rtc {
             i2c,
-            address
+            address,
+            hour_mode: HourMode::_24Hour,
+            first_weekday: Weekday::Monday,
+            converting: false
+        }
+    }
+
+    /// Set mode of hours written to chip, reading detects mode by itself
+    pub fn hour_mode(mut self, mode: HourMode) -> Self {
+        self.hour_mode = mode;
+        self
+    }
+
+    /// Write status register, alarm flags are written as 1 (no change)
+    /// unless cleared
+    ///
+    /// Writing back flags as read could clear alarm which fired in between.
+    fn update_status(&mut self, mask: u8, value: u8, clear: u8) -> Result<(), RtcError<E>> {
+        let current = self.read_register(ControlData::StatusReg as u8)?;
+        let value = (current & !mask) | (value & mask) | ALARM1_FLAG | ALARM2_FLAG;
+
+        self.write_register(ControlData::StatusReg as u8, value & !clear)
+    }
+
+    /// Set weekday stored as 1 in day register (Monday by default)
+    ///
+    /// Numbering must match weekdays of `Alarm1::Weekday` and `Alarm2::Weekday`.
+    pub fn first_weekday(mut self, first: Weekday) -> Self {
+        self.first_weekday = first;
+        self
+    }
+
+    /// Read day register, `None` if it is out of range [1-7]
+    pub fn get_stored_weekday(&mut self) -> Result<Option<Weekday>, RtcError<E>> {
+        let value = self.read_register(0x03)?;
+
+        Ok(Weekday::from_number(value & 0x07, self.first_weekday))
+    }
+
+    fn alarm_day(date: u8, weekday: u8) -> Result<u8, RtcError<E>> {
+        match weekday {
+            0 => Ok(check(date, 1, 31)?),
+            _ => Ok(ALARM_WEEKDAY | check(weekday, 1, 7)?)
+        }
+    }
+
+    /// Write alarm 1, interrupt is not changed
+    pub fn set_alarm1(&mut self, alarm: &Alarm1) -> Result<(), RtcError<E>> {
+        let (seconds, minutes, hours, date, weekday, mask) = match *alarm {
+            Alarm1::EverySecond => (0, 0, 0, 0, 0, 0b1111),
+            Alarm1::Seconds { seconds } => (seconds, 0, 0, 0, 0, 0b1110),
+            Alarm1::Minutes { minutes, seconds } => (seconds, minutes, 0, 0, 0, 0b1100),
+            Alarm1::Hours { hours, minutes, seconds } => (seconds, minutes, hours, 0, 0, 0b1000),
+            Alarm1::Date { date, hours, minutes, seconds } => (seconds, minutes, hours, date, 0, 0b0000),
+            Alarm1::Weekday { weekday, hours, minutes, seconds } => (seconds, minutes, hours, 0, weekday, 0b0000)
+        };
+
+        let mask_bit = |bit: u8| if mask & (1 << bit) != 0 { ALARM_MASK } else { 0 };
+
+        let data = [
+            AlarmData::Alarm1 as u8,
+            check(seconds, 0, 59)? | mask_bit(0),
+            check(minutes, 0, 59)? | mask_bit(1),
+            encode_hours(hours, self.hour_mode, HOUR_12)? | mask_bit(2),
+            match mask_bit(3) {
+                0 => Self::alarm_day(date, weekday)?,
+                _ => ALARM_MASK
+            }
+        ];
+
+        self.write(&data)
+    }
+
+    /// Read alarm 1
+    pub fn get_alarm1(&mut self) -> Result<Alarm1, RtcError<E>> {
+        let mut data = [0_u8; 4];
+
+        self.read_registers(AlarmData::Alarm1 as u8, &mut data)?;
+
+        let mask = data.iter().enumerate()
+            .fold(0_u8, |mask, (bit, &value)| mask | ((value >> 7) << bit));
+
+        let seconds = bcd_to_bin(data[0] & 0x7F);
+        let minutes = bcd_to_bin(data[1] & 0x7F);
+        let hours = decode_hours(data[2] & 0x7F, data[2] & HOUR_12 != 0);
+        let day = bcd_to_bin(data[3] & 0x3F);
+
+        match mask {
+            0b1111 => Ok(Alarm1::EverySecond),
+            0b1110 => Ok(Alarm1::Seconds { seconds }),
+            0b1100 => Ok(Alarm1::Minutes { minutes, seconds }),
+            0b1000 => Ok(Alarm1::Hours { hours, minutes, seconds }),
+            0b0000 if data[3] & ALARM_WEEKDAY != 0 => Ok(Alarm1::Weekday { weekday: day, hours, minutes, seconds }),
+            0b0000 => Ok(Alarm1::Date { date: day, hours, minutes, seconds }),
+            _ => Err(RtcError::WrongValue)
         }
     }
 
-    fn bcd_to_bin(value: u8) -> u8 {
-        value - 6 * (value >> 4)
+    /// Write alarm 2, interrupt is not changed
+    pub fn set_alarm2(&mut self, alarm: &Alarm2) -> Result<(), RtcError<E>> {
+        let (minutes, hours, date, weekday, mask) = match *alarm {
+            Alarm2::EveryMinute => (0, 0, 0, 0, 0b111),
+            Alarm2::Minutes { minutes } => (minutes, 0, 0, 0, 0b110),
+            Alarm2::Hours { hours, minutes } => (minutes, hours, 0, 0, 0b100),
+            Alarm2::Date { date, hours, minutes } => (minutes, hours, date, 0, 0b000),
+            Alarm2::Weekday { weekday, hours, minutes } => (minutes, hours, 0, weekday, 0b000)
+        };
+
+        let mask_bit = |bit: u8| if mask & (1 << bit) != 0 { ALARM_MASK } else { 0 };
+
+        let data = [
+            AlarmData::Alarm2 as u8,
+            check(minutes, 0, 59)? | mask_bit(0),
+            encode_hours(hours, self.hour_mode, HOUR_12)? | mask_bit(1),
+            match mask_bit(2) {
+                0 => Self::alarm_day(date, weekday)?,
+                _ => ALARM_MASK
+            }
+        ];
+
+        self.write(&data)
     }
 
-    fn bin_to_bcd(value: u8) -> u8 {
-        value + 6 * (value / 10)
+    /// Read alarm 2
+    pub fn get_alarm2(&mut self) -> Result<Alarm2, RtcError<E>> {
+        let mut data = [0_u8; 3];
+
+        self.read_registers(AlarmData::Alarm2 as u8, &mut data)?;
+
+        let mask = data.iter().enumerate()
+            .fold(0_u8, |mask, (bit, &value)| mask | ((value >> 7) << bit));
+
+        let minutes = bcd_to_bin(data[0] & 0x7F);
+        let hours = decode_hours(data[1] & 0x7F, data[1] & HOUR_12 != 0);
+        let day = bcd_to_bin(data[2] & 0x3F);
+
+        match mask {
+            0b111 => Ok(Alarm2::EveryMinute),
+            0b110 => Ok(Alarm2::Minutes { minutes }),
+            0b100 => Ok(Alarm2::Hours { hours, minutes }),
+            0b000 if data[2] & ALARM_WEEKDAY != 0 => Ok(Alarm2::Weekday { weekday: day, hours, minutes }),
+            0b000 => Ok(Alarm2::Date { date: day, hours, minutes }),
+            _ => Err(RtcError::WrongValue)
+        }
     }
 
-    fn write(&mut self, data: &[u8]) -> Result<(), RtcError> {
-        self.i2c.write(self.address, data)
-            .map_err(|_| { RtcError::I2cError })
+    /// Enable or disable alarm 1 interrupt (A1IE) on INT/SQW pin
+    ///
+    /// Enabling switches INT/SQW pin to interrupt output (INTCN).
+    pub fn enable_alarm1_interrupt(&mut self, enable: bool) -> Result<(), RtcError<E>> {
+        self.enable_alarm_interrupt(ALARM1_INTERRUPT_ENABLE, enable)
     }
 
-    fn read(&mut self, data: &mut [u8]) -> Result<(), RtcError> {
-        self.i2c.read(self.address, data)
-            .map_err(|_| { RtcError::I2cError } )
+    /// Enable or disable alarm 2 interrupt (A2IE) on INT/SQW pin
+    ///
+    /// Enabling switches INT/SQW pin to interrupt output (INTCN).
+    pub fn enable_alarm2_interrupt(&mut self, enable: bool) -> Result<(), RtcError<E>> {
+        self.enable_alarm_interrupt(ALARM2_INTERRUPT_ENABLE, enable)
+    }
+
+    fn enable_alarm_interrupt(&mut self, bit: u8, enable: bool) -> Result<(), RtcError<E>> {
+        let (mask, value) = match enable {
+            true => (bit | INTERRUPT_CONTROL, bit | INTERRUPT_CONTROL),
+            false => (bit, 0_u8)
+        };
+
+        self.update_register(ControlData::Control as u8, mask, value)
+    }
+
+    /// Read alarm 1 flag (A1F)
+    pub fn has_alarm1_matched(&mut self) -> Result<bool, RtcError<E>> {
+        Ok(self.read_register(ControlData::StatusReg as u8)? & ALARM1_FLAG != 0)
+    }
+
+    /// Read alarm 2 flag (A2F)
+    pub fn has_alarm2_matched(&mut self) -> Result<bool, RtcError<E>> {
+        Ok(self.read_register(ControlData::StatusReg as u8)? & ALARM2_FLAG != 0)
+    }
+
+    /// Clear alarm 1 flag (A1F), releases INT/SQW pin
+    pub fn clear_alarm1_matched(&mut self) -> Result<(), RtcError<E>> {
+        self.update_status(0_u8, 0_u8, ALARM1_FLAG)
+    }
+
+    /// Clear alarm 2 flag (A2F), releases INT/SQW pin
+    pub fn clear_alarm2_matched(&mut self) -> Result<(), RtcError<E>> {
+        self.update_status(0_u8, 0_u8, ALARM2_FLAG)
+    }
+
+    /// Read control register
+    pub fn get_control(&mut self) -> Result<Control, RtcError<E>> {
+        Ok(Control::from_register(self.read_register(ControlData::Control as u8)?))
+    }
+
+    /// Write control register, running temperature conversion is kept
+    pub fn set_control(&mut self, control: &Control) -> Result<(), RtcError<E>> {
+        self.update_register(ControlData::Control as u8, !CONVERT_TEMPERATURE, control.to_register())
+    }
+
+    /// Read status register
+    pub fn get_status(&mut self) -> Result<Status, RtcError<E>> {
+        Ok(Status::from_register(self.read_register(ControlData::StatusReg as u8)?))
+    }
+
+    /// Check that oscillator was not stopped (OSF) since time was set
+    pub fn is_time_valid(&mut self) -> Result<bool, RtcError<E>> {
+        Ok(!self.get_status()?.oscillator_stopped)
+    }
+
+    /// Enable or disable oscillator on battery (inverted EOSC)
+    pub fn enable_oscillator_on_battery(&mut self, enable: bool) -> Result<(), RtcError<E>> {
+        let value = if enable { 0_u8 } else { ENABLE_OSCILLATOR };
+
+        self.update_register(ControlData::Control as u8, ENABLE_OSCILLATOR, value)
+    }
+
+    /// Enable or disable square wave when chip runs on battery (BBSQW)
+    pub fn enable_battery_square_wave(&mut self, enable: bool) -> Result<(), RtcError<E>> {
+        let value = if enable { BATTERY_SQUARE_WAVE } else { 0_u8 };
+
+        self.update_register(ControlData::Control as u8, BATTERY_SQUARE_WAVE, value)
+    }
+
+    /// Output square wave on INT/SQW pin, alarm interrupts stop driving pin (INTCN = 0)
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use embedded_hal::blocking::i2c::{Write, WriteRead};
+    /// # use device_drivers::i2c::rtc::ds3231::Rtc;
+    /// # fn example<I2C, E>(mut rtc: Rtc<I2C>)
+    /// #     where I2C: Write<Error = E> + WriteRead<Error = E>, E: core::fmt::Debug
+    /// # {
+    /// use device_drivers::i2c::rtc::ds3231::SquareWaveFrequency;
+    ///
+    /// rtc.set_square_wave(SquareWaveFrequency::_1Hz).unwrap();
+    /// # }
+    /// ```
+    pub fn set_square_wave(&mut self, frequency: SquareWaveFrequency) -> Result<(), RtcError<E>> {
+        self.update_register(
+            ControlData::Control as u8,
+            RATE_SELECT | INTERRUPT_CONTROL,
+            frequency.to_register()
+        )
+    }
+
+    /// Use INT/SQW pin as interrupt output (INTCN = 1)
+    pub fn disable_square_wave(&mut self) -> Result<(), RtcError<E>> {
+        self.update_register(ControlData::Control as u8, INTERRUPT_CONTROL, INTERRUPT_CONTROL)
+    }
+
+    /// Enable or disable 32 kHz output (EN32kHz)
+    pub fn enable_32khz_output(&mut self, enable: bool) -> Result<(), RtcError<E>> {
+        let value = if enable { ENABLE_32KHZ } else { 0_u8 };
+
+        self.update_status(ENABLE_32KHZ, value, 0_u8)
+    }
+
+    /// Read busy flag (BSY) of temperature conversion
+    pub fn is_busy(&mut self) -> Result<bool, RtcError<E>> {
+        Ok(self.get_status()?.busy)
+    }
+
+    /// Read aging offset (one step is about 0.1 ppm, positive slows clock)
+    pub fn get_aging_offset(&mut self) -> Result<i8, RtcError<E>> {
+        Ok(self.read_register(CalibrationData::AgingOffset as u8)? as i8)
+    }
+
+    /// Write aging offset, applied by chip after next temperature conversion
+    pub fn set_aging_offset(&mut self, offset: i8) -> Result<(), RtcError<E>> {
+        self.write_register(CalibrationData::AgingOffset as u8, offset as u8)
+    }
+
+    /// Compensate drift measured between two samples by aging offset
+    ///
+    /// Aging offset must be the same during whole measurement. Returns new
+    /// aging offset, clamped to register range.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use embedded_hal::blocking::i2c::{Write, WriteRead};
+    /// # use device_drivers::i2c::rtc::ds3231::Rtc;
+    /// # fn example<I2C, E>(mut rtc: Rtc<I2C>, gps_time: impl Fn() -> u32, rtc_time: impl Fn() -> u32)
+    /// #     where I2C: Write<Error = E> + WriteRead<Error = E>, E: core::fmt::Debug
+    /// # {
+    /// use device_drivers::i2c::rtc::ds3231::DriftSample;
+    ///
+    /// let start = DriftSample { reference: gps_time(), rtc: rtc_time() };
+    /// // ... some weeks later
+    /// let end = DriftSample { reference: gps_time(), rtc: rtc_time() };
+    ///
+    /// rtc.calibrate(&start, &end).unwrap();
+    /// nb::block!(rtc.convert_temperature()).unwrap();
+    /// # }
+    /// ```
+    pub fn calibrate(&mut self, start: &DriftSample, end: &DriftSample) -> Result<i8, RtcError<E>> {
+        let drift = drift(start, end).ok_or(RtcError::WrongValue)?;
+        let offset = (self.get_aging_offset()? as i32 + drift)
+            .max(i8::MIN as i32)
+            .min(i8::MAX as i32) as i8;
+
+        self.set_aging_offset(offset)?;
+
+        Ok(offset)
+    }
+
+    /// Read temperature in quarters of °C
+    pub fn get_temperature_raw(&mut self) -> Result<i16, RtcError<E>> {
+        let mut data = [0_u8; 2];
+
+        self.read_registers(TemperatureData::Msb as u8, &mut data)?;
+
+        Ok(((data[0] as i8 as i16) << 2) | (data[1] >> 6) as i16)
+    }
+
+    /// Read temperature in °C (0.25 °C resolution)
+    ///
+    /// Chip updates temperature every 64 seconds, use `convert_temperature`
+    /// for fresh value.
+    pub fn get_temperature(&mut self) -> Result<f32, RtcError<E>> {
+        Ok(self.get_temperature_raw()? as f32 / 4.0)
+    }
+
+    /// Force temperature conversion (CONV), waits while chip is busy (BSY)
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use embedded_hal::blocking::i2c::{Write, WriteRead};
+    /// # use device_drivers::i2c::rtc::ds3231::Rtc;
+    /// # fn example<I2C, E>(mut rtc: Rtc<I2C>)
+    /// #     where I2C: Write<Error = E> + WriteRead<Error = E>, E: core::fmt::Debug
+    /// # {
+    /// nb::block!(rtc.convert_temperature()).unwrap();
+    /// let temperature = rtc.get_temperature().unwrap();
+    /// # }
+    /// ```
+    pub fn convert_temperature(&mut self) -> nb::Result<(), RtcError<E>> {
+        let control = self.read_register(ControlData::Control as u8)?;
+
+        if control & CONVERT_TEMPERATURE != 0 {
+            return Err(nb::Error::WouldBlock);
+        }
+
+        if self.converting {
+            self.converting = false;
+            return Ok(());
+        }
+
+        if self.is_busy()? {
+            return Err(nb::Error::WouldBlock);
+        }
+
+        self.write_register(ControlData::Control as u8, control | CONVERT_TEMPERATURE)?;
+        self.converting = true;
+
+        Err(nb::Error::WouldBlock)
     }
 }
 
-impl From<DateTimeErrors> for RtcError {
-    fn from(_: DateTimeErrors) -> Self {
-        RtcError::WrongValue
+impl_registers!();
+
+impl<E> ErrorTrait for RtcError<E>
+    where
+        E: Debug
+{
+    fn kind(&self) -> ErrorKind {
+        match self {
+            RtcError::WrongValue => ErrorKind::WrongValue,
+            RtcError::I2cError(_) => ErrorKind::Bus,
+            RtcError::OscillatorStopped => ErrorKind::TimeInvalid
+        }
     }
 }
 
-impl<I2CType> RtcTrait<DateTime> for Rtc<I2CType>
+impl<I2CType, E> RtcTrait<DateTime> for Rtc<I2CType>
     where
-        I2CType: Read + Write
+        I2CType: Write<Error = E> + WriteRead<Error = E>,
+        E: Debug
 {
-    type Error = RtcError;
+    type Error = RtcError<E>;
 
+    /// Write time, years [2000-2199] are supported
+    ///
+    /// All time registers are written in one transaction starting from seconds,
+    /// writing seconds resets countdown chain, so no rollover happens in between.
     fn set(&mut self, datetime: &DateTime) -> Result<(), Self::Error> {
+        let year = datetime.get_year()?;
+
+        if !(MIN_YEAR..=MAX_YEAR).contains(&year) { return Err(RtcError::WrongValue); }
+
+        let century = if year >= MIN_YEAR + 100 { CENTURY } else { 0_u8 };
+
         let data_to_send = [
             0_u8,
-            Self::bin_to_bcd(datetime.get_seconds()?),
-            Self::bin_to_bcd(datetime.get_minutes()?),
-            Self::bin_to_bcd(datetime.get_hours()?),
-            Self::bin_to_bcd(0_u8),
-            Self::bin_to_bcd(datetime.get_day()?),
-            Self::bin_to_bcd(datetime.get_month()?),
-            Self::bin_to_bcd((datetime.get_year()? - 2000) as u8)
+            bin_to_bcd(datetime.get_seconds()?),
+            bin_to_bcd(datetime.get_minutes()?),
+            encode_hours(datetime.get_hours()?, self.hour_mode, HOUR_12)?,
+            datetime.get_weekday()?.number_from(self.first_weekday),
+            bin_to_bcd(datetime.get_day()?),
+            bin_to_bcd(datetime.get_month()?) | century,
+            bin_to_bcd((year % 100) as u8)
         ];
 
         self.write(&data_to_send)?;
 
-        let mut status_reg = [0_u8];
-
-        self.read(&mut status_reg)?;
-        status_reg[0] &= !0x80;
-
-        let output_data: [u8; 2] = [ControlData::StatusReg as u8, status_reg[0]];
-        self.write(&output_data)?;
-
-        Ok(())
+        self.update_status(0_u8, 0_u8, OSCILLATOR_STOP_FLAG)
     }
 
+    /// Read time, fails with `OscillatorStopped` if time is invalid
+    ///
+    /// Weekday is calculated from the date, day register as written by chip
+    /// is read by `get_stored_weekday`.
+    ///
+    /// Chip copies time to read buffer on start condition, so one burst read
+    /// is consistent snapshot even on seconds rollover.
     fn get(&mut self) -> Result<DateTime, Self::Error> {
-        let mut data_to_read = [0_u8; 7];
+        let mut data_to_read = [0_u8; ControlData::StatusReg as usize + 1];
+
+        self.read_registers(0_u8, &mut data_to_read)?;
 
-        self.write(&[0_u8])?;
+        if data_to_read[ControlData::StatusReg as usize] & OSCILLATOR_STOP_FLAG != 0 {
+            return Err(RtcError::OscillatorStopped);
+        }
 
-        self.read(&mut data_to_read)?;
+        let century = match data_to_read[5] & CENTURY {
+            0 => MIN_YEAR,
+            _ => MIN_YEAR + 100
+        };
 
         Ok(
-            DateTime::new()
-                .seconds(Self::bcd_to_bin(data_to_read[0] & 0x7F))
-                .minutes(Self::bcd_to_bin(data_to_read[1]))
-                .hours(Self::bcd_to_bin(data_to_read[2]))
-                .day(Self::bcd_to_bin(data_to_read[4]))
-                .month(Self::bcd_to_bin(data_to_read[5]))
-                .year(2000_u16 + (Self::bcd_to_bin(data_to_read[6]) as u16))
+            DateTime::from_ymd_hms(
+                century + (bcd_to_bin(data_to_read[6]) as u16),
+                bcd_to_bin(data_to_read[5] & 0x1F),
+                bcd_to_bin(data_to_read[4] & 0x3F),
+                decode_hours(data_to_read[2] & 0x7F, data_to_read[2] & HOUR_12 != 0),
+                bcd_to_bin(data_to_read[1] & 0x7F),
+                bcd_to_bin(data_to_read[0] & 0x7F)
+            )?
         )
     }
 }
+
+/// Alarm 1 is used, it matches seconds too
+impl<I2CType, E> AlarmTrait for Rtc<I2CType>
+    where
+        I2CType: Write<Error = E> + WriteRead<Error = E>,
+        E: Debug
+{
+    type Alarm = Alarm1;
+
+    fn set_alarm(&mut self, alarm: &Alarm1) -> Result<(), Self::Error> {
+        self.set_alarm1(alarm)
+    }
+
+    fn get_alarm(&mut self) -> Result<Alarm1, Self::Error> {
+        self.get_alarm1()
+    }
+
+    fn enable_alarm_interrupt(&mut self, enable: bool) -> Result<(), Self::Error> {
+        self.enable_alarm1_interrupt(enable)
+    }
+
+    fn has_alarm_matched(&mut self) -> Result<bool, Self::Error> {
+        self.has_alarm1_matched()
+    }
+
+    fn clear_alarm_matched(&mut self) -> Result<(), Self::Error> {
+        self.clear_alarm1_matched()
+    }
+}
+
+impl<I2CType, E> TemperatureTrait for Rtc<I2CType>
+    where
+        I2CType: Write<Error = E> + WriteRead<Error = E>,
+        E: Debug
+{
+    fn get_temperature(&mut self) -> Result<f32, Self::Error> {
+        Rtc::get_temperature(self)
+    }
+}
+
+impl<I2CType, E> SquareWaveTrait for Rtc<I2CType>
+    where
+        I2CType: Write<Error = E> + WriteRead<Error = E>,
+        E: Debug
+{
+    type Frequency = SquareWaveFrequency;
+
+    fn set_square_wave(&mut self, frequency: SquareWaveFrequency) -> Result<(), Self::Error> {
+        Rtc::set_square_wave(self, frequency)
+    }
+
+    fn disable_square_wave(&mut self) -> Result<(), Self::Error> {
+        Rtc::disable_square_wave(self)
+    }
+}
+
+/// Aging offset is used, one step is about 0.1 ppm
+impl<I2CType, E> CalibrationTrait for Rtc<I2CType>
+    where
+        I2CType: Write<Error = E> + WriteRead<Error = E>,
+        E: Debug
+{
+    fn get_correction(&mut self) -> Result<i32, Self::Error> {
+        Ok(-(self.get_aging_offset()? as i32))
+    }
+
+    fn set_correction(&mut self, correction: i32) -> Result<(), Self::Error> {
+        let offset = correction.checked_neg()
+            .and_then(|offset| i8::try_from(offset).ok())
+            .ok_or(RtcError::WrongValue)?;
+
+        self.set_aging_offset(offset)
+    }
+}
+
+#[cfg(feature = "rtcc")]
+impl<I2CType, E> rtcc::DateTimeAccess for Rtc<I2CType>
+    where
+        I2CType: Write<Error = E> + WriteRead<Error = E>,
+        E: Debug
+{
+    type Error = RtcError<E>;
+
+    fn datetime(&mut self) -> Result<rtcc::NaiveDateTime, Self::Error> {
+        Ok(self.get()?.into())
+    }
+
+    fn set_datetime(&mut self, datetime: &rtcc::NaiveDateTime) -> Result<(), Self::Error> {
+        self.set(&DateTime::try_from(*datetime)?)
+    }
+}
+
+/// Weekday is raw day register [1-7], see `first_weekday`
+#[cfg(feature = "rtcc")]
+impl<I2CType, E> rtcc::Rtcc for Rtc<I2CType>
+    where
+        I2CType: Write<Error = E> + WriteRead<Error = E>,
+        E: Debug
+{
+    fn seconds(&mut self) -> Result<u8, Self::Error> {
+        Ok(bcd_to_bin(self.read_register(0x00)? & 0x7F))
+    }
+
+    fn minutes(&mut self) -> Result<u8, Self::Error> {
+        Ok(bcd_to_bin(self.read_register(0x01)? & 0x7F))
+    }
+
+    fn hours(&mut self) -> Result<rtcc::Hours, Self::Error> {
+        let value = self.read_register(0x02)?;
+
+        if value & HOUR_12 == 0 {
+            return Ok(rtcc::Hours::H24(bcd_to_bin(value & 0x3F)));
+        }
+
+        let hours = bcd_to_bin(value & 0x1F);
+
+        match value & HOUR_PM {
+            0 => Ok(rtcc::Hours::AM(hours)),
+            _ => Ok(rtcc::Hours::PM(hours))
+        }
+    }
+
+    fn time(&mut self) -> Result<rtcc::NaiveTime, Self::Error> {
+        Ok(rtcc::NaiveDateTime::from(self.get()?).time())
+    }
+
+    fn weekday(&mut self) -> Result<u8, Self::Error> {
+        Ok(self.read_register(0x03)? & 0x07)
+    }
+
+    fn day(&mut self) -> Result<u8, Self::Error> {
+        Ok(bcd_to_bin(self.read_register(0x04)? & 0x3F))
+    }
+
+    fn month(&mut self) -> Result<u8, Self::Error> {
+        Ok(bcd_to_bin(self.read_register(0x05)? & 0x1F))
+    }
+
+    fn year(&mut self) -> Result<u16, Self::Error> {
+        let mut data = [0_u8; 2];
+
+        self.read_registers(0x05, &mut data)?;
+
+        let century = match data[0] & CENTURY {
+            0 => MIN_YEAR,
+            _ => MIN_YEAR + 100
+        };
+
+        Ok(century + bcd_to_bin(data[1]) as u16)
+    }
+
+    fn date(&mut self) -> Result<rtcc::NaiveDate, Self::Error> {
+        Ok(rtcc::NaiveDateTime::from(self.get()?).date())
+    }
+
+    fn set_seconds(&mut self, seconds: u8) -> Result<(), Self::Error> {
+        self.write_register(0x00, check(seconds, 0, 59)?)
+    }
+
+    fn set_minutes(&mut self, minutes: u8) -> Result<(), Self::Error> {
+        self.write_register(0x01, check(minutes, 0, 59)?)
+    }
+
+    fn set_hours(&mut self, hours: rtcc::Hours) -> Result<(), Self::Error> {
+        let hours = match hours {
+            rtcc::Hours::H24(hours) => hours,
+            rtcc::Hours::AM(hours) if (1..=12).contains(&hours) => hours % 12,
+            rtcc::Hours::PM(hours) if (1..=12).contains(&hours) => hours % 12 + 12,
+            _ => return Err(RtcError::WrongValue)
+        };
+
+        let value = encode_hours(hours, self.hour_mode, HOUR_12)?;
+
+        self.write_register(0x02, value)
+    }
+
+    fn set_time(&mut self, time: &rtcc::NaiveTime) -> Result<(), Self::Error> {
+        use rtcc::Timelike;
+
+        let data = [
+            0x00,
+            check(time.second() as u8, 0, 59)?,
+            check(time.minute() as u8, 0, 59)?,
+            encode_hours(time.hour() as u8, self.hour_mode, HOUR_12)?
+        ];
+
+        self.write(&data)
+    }
+
+    fn set_weekday(&mut self, weekday: u8) -> Result<(), Self::Error> {
+        self.write_register(0x03, check(weekday, 1, 7)?)
+    }
+
+    fn set_day(&mut self, day: u8) -> Result<(), Self::Error> {
+        self.write_register(0x04, check(day, 1, 31)?)
+    }
+
+    fn set_month(&mut self, month: u8) -> Result<(), Self::Error> {
+        let month = check(month, 1, 12)?;
+
+        self.update_register(0x05, !CENTURY, month)
+    }
+
+    fn set_year(&mut self, year: u16) -> Result<(), Self::Error> {
+        if !(MIN_YEAR..=MAX_YEAR).contains(&year) { return Err(RtcError::WrongValue); }
+
+        let century = if year >= MIN_YEAR + 100 { CENTURY } else { 0_u8 };
+
+        self.update_register(0x05, CENTURY, century)?;
+        self.write_register(0x06, bin_to_bcd((year % 100) as u8))
+    }
+
+    fn set_date(&mut self, date: &rtcc::NaiveDate) -> Result<(), Self::Error> {
+        let datetime = DateTime::try_from(date.and_hms_opt(0, 0, 0).ok_or(RtcError::WrongValue)?)?;
+        let year = datetime.get_year()?;
+
+        if !(MIN_YEAR..=MAX_YEAR).contains(&year) { return Err(RtcError::WrongValue); }
+
+        let century = if year >= MIN_YEAR + 100 { CENTURY } else { 0_u8 };
+
+        let data = [
+            0x03,
+            datetime.get_weekday()?.number_from(self.first_weekday),
+            bin_to_bcd(datetime.get_day()?),
+            bin_to_bcd(datetime.get_month()?) | century,
+            bin_to_bcd((year % 100) as u8)
+        ];
+
+        self.write(&data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i2c::mock::{Device, DumpI2c, FailingI2c};
+
+    const ADDRESS: u8 = 0x68;
+
+    const STATUS_FLAGS: u8 = OSCILLATOR_STOP_FLAG | ALARM2_FLAG | ALARM1_FLAG;
+
+    #[derive(Default)]
+    struct Ds3231 {
+        /// Status flags raised right after status register is read
+        raise_on_read: u8
+    }
+
+    impl Device for Ds3231 {
+        fn write(&mut self, registers: &mut [u8], pointer: usize, value: u8) {
+            registers[pointer] = match pointer {
+                // status flags can be cleared only
+                0x0F => (value & !STATUS_FLAGS) | (value & registers[0x0F] & STATUS_FLAGS),
+                _ => value
+            };
+        }
+
+        fn read(&mut self, registers: &mut [u8], pointer: usize) -> u8 {
+            let value = registers[pointer];
+            if pointer == 0x0F { registers[0x0F] |= self.raise_on_read; }
+
+            value
+        }
+    }
+
+    fn rtc() -> Rtc<DumpI2c<Ds3231>> {
+        Rtc::new(DumpI2c::with_device(ADDRESS, 0x13, Ds3231::default()), ADDRESS)
+    }
+
+    #[test]
+    fn test_alarm1() {
+        let mut rtc = rtc();
+
+        let alarm = Alarm1::Weekday { weekday: 3, hours: 23, minutes: 59, seconds: 30 };
+        rtc.set_alarm1(&alarm).unwrap();
+        assert_eq!(rtc.i2c.registers[0x07..0x0B], [0x30, 0x59, 0x23, 0x43]);
+        assert_eq!(rtc.get_alarm1().unwrap(), alarm);
+
+        let alarm = Alarm1::Minutes { minutes: 15, seconds: 0 };
+        rtc.set_alarm1(&alarm).unwrap();
+        assert_eq!(rtc.i2c.registers[0x07..0x0B], [0x00, 0x15, 0x80, 0x80]);
+        assert_eq!(rtc.get_alarm1().unwrap(), alarm);
+
+        assert!(rtc.set_alarm1(&Alarm1::Date { date: 0, hours: 0, minutes: 0, seconds: 0 }).is_err());
+    }
+
+    #[test]
+    fn test_alarm2() {
+        let mut rtc = rtc();
+
+        let alarm = Alarm2::Date { date: 31, hours: 7, minutes: 5 };
+        rtc.set_alarm2(&alarm).unwrap();
+        assert_eq!(rtc.i2c.registers[0x0B..0x0E], [0x05, 0x07, 0x31]);
+        assert_eq!(rtc.get_alarm2().unwrap(), alarm);
+
+        rtc.set_alarm2(&Alarm2::EveryMinute).unwrap();
+        assert_eq!(rtc.get_alarm2().unwrap(), Alarm2::EveryMinute);
+    }
+
+    #[test]
+    fn test_alarm_interrupt_and_flags() {
+        let mut rtc = rtc();
+        rtc.i2c.registers[0x0E] = 0x1C;
+        rtc.i2c.registers[0x0F] = 0x8B;
+
+        rtc.enable_alarm2_interrupt(true).unwrap();
+        assert_eq!(rtc.i2c.registers[0x0E], 0x1E);
+
+        assert!(rtc.has_alarm1_matched().unwrap());
+        rtc.clear_alarm1_matched().unwrap();
+        assert_eq!(rtc.i2c.registers[0x0F], 0x8A);
+        assert!(!rtc.has_alarm1_matched().unwrap());
+        assert!(rtc.has_alarm2_matched().unwrap());
+    }
+
+    #[test]
+    fn test_flag_raised_while_clearing() {
+        let mut rtc = rtc();
+        rtc.i2c.registers[0x0F] = ALARM1_FLAG;
+        rtc.i2c.device.raise_on_read = ALARM2_FLAG;
+
+        rtc.clear_alarm1_matched().unwrap();
+        assert_eq!(rtc.i2c.registers[0x0F], ALARM2_FLAG);
+
+        rtc.i2c.device.raise_on_read = ALARM1_FLAG;
+        rtc.clear_alarm2_matched().unwrap();
+        assert_eq!(rtc.i2c.registers[0x0F], ALARM1_FLAG);
+
+        rtc.i2c.registers[0x0F] = OSCILLATOR_STOP_FLAG;
+        rtc.i2c.device.raise_on_read = ALARM2_FLAG;
+        rtc.set(&DateTime::from_ymd_hms(2021, 3, 14, 15, 9, 26).unwrap()).unwrap();
+        assert_eq!(rtc.i2c.registers[0x0F], ALARM2_FLAG);
+
+        rtc.i2c.device.raise_on_read = ALARM1_FLAG;
+        rtc.enable_32khz_output(true).unwrap();
+        assert_eq!(rtc.i2c.registers[0x0F], ENABLE_32KHZ | ALARM2_FLAG | ALARM1_FLAG);
+    }
+
+    #[test]
+    fn test_control() {
+        let mut rtc = rtc();
+        rtc.i2c.registers[0x0E] = 0x1C;
+
+        let mut control = rtc.get_control().unwrap();
+        assert_eq!(control.square_wave_frequency, SquareWaveFrequency::_8192Hz);
+        assert!(control.oscillator_on_battery);
+        assert!(control.interrupt_control);
+
+        control.oscillator_on_battery = false;
+        control.alarm1_interrupt = true;
+        rtc.set_control(&control).unwrap();
+        assert_eq!(rtc.i2c.registers[0x0E], 0x9D);
+
+        rtc.set_square_wave(SquareWaveFrequency::_1Hz).unwrap();
+        assert_eq!(rtc.i2c.registers[0x0E], 0x81);
+    }
+
+    #[test]
+    fn test_status() {
+        let mut rtc = rtc();
+        rtc.i2c.registers[0x0F] = 0x88;
+
+        let status = rtc.get_status().unwrap();
+        assert!(status.oscillator_stopped);
+        assert!(status.enable_32khz);
+        assert!(!status.busy);
+
+        rtc.enable_32khz_output(false).unwrap();
+        assert_eq!(rtc.i2c.registers[0x0F], 0x80);
+    }
+
+    #[test]
+    fn test_oscillator_stopped() {
+        let mut rtc = rtc();
+        rtc.i2c.registers[0x0F] = 0x88;
+
+        assert!(!rtc.is_time_valid().unwrap());
+        assert!(matches!(rtc.get(), Err(RtcError::OscillatorStopped)));
+
+        let datetime = DateTime::from_ymd_hms(2021, 3, 14, 15, 9, 26).unwrap();
+        rtc.set(&datetime).unwrap();
+        assert_eq!(rtc.i2c.registers[0x0F], 0x08);
+        assert_eq!(rtc.i2c.registers[0x00..0x07], [0x26, 0x09, 0x15, 0x07, 0x14, 0x03, 0x21]);
+
+        assert!(rtc.is_time_valid().unwrap());
+        assert_eq!(rtc.get_stored_weekday().unwrap(), Some(Weekday::Sunday));
+        assert_eq!(rtc.get().unwrap().get_minutes().ok(), Some(9));
+    }
+
+    #[test]
+    fn test_drift() {
+        let start = DriftSample { reference: 1_000_000, rtc: 1_000_010 };
+        let end = DriftSample { reference: 3_592_000, rtc: 3_592_036 };
+        assert_eq!(drift(&start, &end).unwrap(), 100);
+
+        let end = DriftSample { reference: 3_592_000, rtc: 3_591_997 };
+        assert_eq!(drift(&start, &end).unwrap(), -50);
+
+        assert!(drift(&end, &start).is_none());
+    }
+
+    #[test]
+    fn test_calibrate() {
+        let mut rtc = rtc();
+        rtc.i2c.registers[0x10] = (-5_i8) as u8;
+
+        let start = DriftSample { reference: 0, rtc: 0 };
+        let end = DriftSample { reference: 1_000_000, rtc: 1_000_002 };
+
+        assert_eq!(rtc.calibrate(&start, &end).unwrap(), 15);
+        assert_eq!(rtc.get_aging_offset().unwrap(), 15);
+
+        let end = DriftSample { reference: 1_000_000, rtc: 1_000_100 };
+        assert_eq!(rtc.calibrate(&start, &end).unwrap(), 127);
+    }
+
+    #[test]
+    fn test_get_after_pointer_moved() {
+        let mut rtc = rtc();
+        rtc.set(&DateTime::from_ymd_hms(2021, 6, 1, 12, 0, 0).unwrap()).unwrap();
+
+        rtc.i2c.pointer = 0x11;
+        assert_eq!(rtc.get().unwrap().get_hours().ok(), Some(12));
+    }
+
+    #[test]
+    fn test_12_hour_mode() {
+        let mut rtc = rtc().hour_mode(HourMode::_12Hour);
+
+        let datetime = DateTime::from_ymd_hms(2021, 3, 14, 0, 30, 0).unwrap();
+        rtc.set(&datetime).unwrap();
+        assert_eq!(rtc.i2c.registers[0x02], 0x52);
+        assert_eq!(rtc.get().unwrap().get_hours().ok(), Some(0));
+
+        rtc.i2c.registers[0x02] = 0x71;
+        assert_eq!(rtc.get().unwrap().get_hours().ok(), Some(23));
+
+        rtc.i2c.registers[0x02] = 0x72;
+        assert_eq!(rtc.get().unwrap().get_hours().ok(), Some(12));
+
+        rtc.set_alarm2(&Alarm2::Hours { hours: 13, minutes: 0 }).unwrap();
+        assert_eq!(rtc.i2c.registers[0x0C], 0x61);
+        assert_eq!(rtc.get_alarm2().unwrap(), Alarm2::Hours { hours: 13, minutes: 0 });
+    }
+
+    #[test]
+    fn test_century() {
+        let mut rtc = rtc();
+
+        let datetime = DateTime::from_ymd_hms(2105, 12, 31, 0, 0, 0).unwrap();
+        rtc.set(&datetime).unwrap();
+        assert_eq!(rtc.i2c.registers[0x05..0x07], [0x92, 0x05]);
+
+        let datetime = rtc.get().unwrap();
+        assert_eq!(datetime.get_month().ok(), Some(12));
+        assert_eq!(datetime.get_year().ok(), Some(2105));
+
+        assert!(matches!(rtc.set(&DateTime::from_ymd_hms(1999, 1, 1, 0, 0, 0).unwrap()), Err(RtcError::WrongValue)));
+        assert!(matches!(rtc.set(&DateTime::from_ymd_hms(2200, 1, 1, 0, 0, 0).unwrap()), Err(RtcError::WrongValue)));
+    }
+
+    #[cfg(feature = "rtcc")]
+    #[test]
+    fn test_rtcc() {
+        use rtcc::{DateTimeAccess, Rtcc, Hours, NaiveDate};
+
+        let mut rtc = rtc().hour_mode(HourMode::_12Hour);
+
+        let datetime = NaiveDate::from_ymd_opt(2021, 3, 14).unwrap().and_hms_opt(15, 9, 26).unwrap();
+        rtc.set_datetime(&datetime).unwrap();
+        assert_eq!(rtc.datetime().unwrap(), datetime);
+        assert_eq!(rtc.hours().unwrap(), Hours::PM(3));
+        assert_eq!(rtc.weekday().unwrap(), 7);
+
+        rtc.set_hours(Hours::AM(12)).unwrap();
+        assert_eq!(rtc.i2c.registers[0x02], 0x52);
+
+        rtc.set_year(2150).unwrap();
+        assert_eq!(rtc.year().unwrap(), 2150);
+        assert_eq!(rtc.month().unwrap(), 3);
+
+        rtc.set_date(&NaiveDate::from_ymd_opt(2024, 2, 29).unwrap()).unwrap();
+        assert_eq!(rtc.date().unwrap(), NaiveDate::from_ymd_opt(2024, 2, 29).unwrap());
+        assert_eq!(rtc.weekday().unwrap(), 4);
+    }
+
+    #[test]
+    fn test_temperature() {
+        let mut rtc = rtc();
+
+        rtc.i2c.registers[0x11] = 0x19;
+        rtc.i2c.registers[0x12] = 0x40;
+        assert_eq!(rtc.get_temperature().unwrap(), 25.25);
+
+        rtc.i2c.registers[0x11] = 0xF6;
+        rtc.i2c.registers[0x12] = 0xC0;
+        assert_eq!(rtc.get_temperature_raw().unwrap(), -37);
+    }
+
+    #[test]
+    fn test_convert_temperature() {
+        let mut rtc = rtc();
+        rtc.i2c.registers[0x0E] = 0x1C;
+        rtc.i2c.registers[0x0F] = BUSY;
+
+        assert!(matches!(rtc.convert_temperature(), Err(nb::Error::WouldBlock)));
+        assert_eq!(rtc.i2c.registers[0x0E], 0x1C);
+
+        rtc.i2c.registers[0x0F] = 0;
+        assert!(matches!(rtc.convert_temperature(), Err(nb::Error::WouldBlock)));
+        assert_eq!(rtc.i2c.registers[0x0E], 0x3C);
+
+        rtc.i2c.registers[0x0E] = 0x1C;
+        assert!(rtc.convert_temperature().is_ok());
+    }
+
+    #[test]
+    fn test_traits() {
+        fn correct<R: CalibrationTrait>(rtc: &mut R, drift: i32) -> Result<(), R::Error> {
+            let correction = rtc.get_correction()?;
+
+            rtc.set_correction(correction - drift)
+        }
+
+        let mut rtc = rtc();
+        correct(&mut rtc, 30).unwrap();
+        correct(&mut rtc, -10).unwrap();
+        assert_eq!(rtc.get_aging_offset().unwrap(), 20);
+        assert_eq!(rtc.set_correction(200).unwrap_err().kind(), ErrorKind::WrongValue);
+        assert_eq!(rtc.set_correction(i32::MIN).unwrap_err().kind(), ErrorKind::WrongValue);
+
+        let mut rtc = Rtc::new(FailingI2c, ADDRESS);
+        assert!(matches!(rtc.get(), Err(RtcError::I2cError(0x2A))));
+        assert_eq!(rtc.get().unwrap_err().kind(), ErrorKind::Bus);
+    }
+}
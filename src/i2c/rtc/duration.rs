@@ -0,0 +1,98 @@
+use core::ops::{Add, Sub, Neg};
+
+const SECONDS_IN_MINUTE: i64 = 60;
+const SECONDS_IN_HOUR: i64 = 60 * SECONDS_IN_MINUTE;
+const SECONDS_IN_DAY: i64 = 24 * SECONDS_IN_HOUR;
+
+/// Signed span of time with seconds resolution
+///
+/// # Example
+///
+/// ```
+/// use device_drivers::i2c::rtc::duration::Duration;
+/// let duration = Duration::from_hours(1) + Duration::from_minutes(30);
+/// assert_eq!(duration.as_minutes(), 90);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Duration {
+    seconds: i64
+}
+
+impl Duration {
+    pub const fn from_seconds(seconds: i64) -> Self {
+        Duration { seconds }
+    }
+
+    pub const fn from_minutes(minutes: i64) -> Self {
+        Duration { seconds: minutes * SECONDS_IN_MINUTE }
+    }
+
+    pub const fn from_hours(hours: i64) -> Self {
+        Duration { seconds: hours * SECONDS_IN_HOUR }
+    }
+
+    pub const fn from_days(days: i64) -> Self {
+        Duration { seconds: days * SECONDS_IN_DAY }
+    }
+
+    /// Whole seconds
+    pub const fn as_seconds(&self) -> i64 {
+        self.seconds
+    }
+
+    /// Whole minutes, rounded toward zero
+    pub const fn as_minutes(&self) -> i64 {
+        self.seconds / SECONDS_IN_MINUTE
+    }
+
+    /// Whole hours, rounded toward zero
+    pub const fn as_hours(&self) -> i64 {
+        self.seconds / SECONDS_IN_HOUR
+    }
+
+    /// Whole days, rounded toward zero
+    pub const fn as_days(&self) -> i64 {
+        self.seconds / SECONDS_IN_DAY
+    }
+
+    pub const fn is_negative(&self) -> bool {
+        self.seconds < 0
+    }
+
+    /// Duration without sign
+    pub const fn abs(&self) -> Self {
+        Duration { seconds: self.seconds.abs() }
+    }
+
+    pub fn checked_add(self, other: Duration) -> Option<Self> {
+        self.seconds.checked_add(other.seconds).map(Duration::from_seconds)
+    }
+
+    pub fn checked_sub(self, other: Duration) -> Option<Self> {
+        self.seconds.checked_sub(other.seconds).map(Duration::from_seconds)
+    }
+}
+
+impl Add for Duration {
+    type Output = Duration;
+
+    fn add(self, other: Duration) -> Duration {
+        Duration::from_seconds(self.seconds + other.seconds)
+    }
+}
+
+impl Sub for Duration {
+    type Output = Duration;
+
+    fn sub(self, other: Duration) -> Duration {
+        Duration::from_seconds(self.seconds - other.seconds)
+    }
+}
+
+impl Neg for Duration {
+    type Output = Duration;
+
+    fn neg(self) -> Duration {
+        Duration::from_seconds(-self.seconds)
+    }
+}
@@ -0,0 +1,737 @@
+use core::convert::TryFrom;
+use core::fmt::Debug;
+use embedded_hal::blocking::{i2c::{Write, WriteRead}};
+use super::traits::{RtcTrait, ErrorTrait, ErrorKind, AlarmTrait, SquareWaveTrait, CalibrationTrait, NvramTrait};
+use super::datetime::{DateTime, Weekday};
+use crate::i2c::rtc::traits::DateTimeTrait;
+use super::common::{Registers, impl_registers, bcd_to_bin, bin_to_bcd, check, decode_hours, encode_hours};
+
+pub use super::common::HourMode;
+
+/// Address of MCP7940N
+pub const DEFAULT_ADDRESS: u8 = 0x6F;
+
+/// Size of battery backed SRAM in bytes
+pub const RAM_SIZE: usize = 64;
+
+#[repr(u8)]
+enum ControlData {
+    Seconds = 0x00,
+    Weekday = 0x03,
+    Control = 0x07,
+    Trimming = 0x08,
+    PowerDown = 0x18,
+    Ram = 0x20
+}
+
+const START_OSCILLATOR: u8 = 0b1000_0000;
+const OSCILLATOR_RUNNING: u8 = 0b0010_0000;
+const POWER_FAIL: u8 = 0b0001_0000;
+const BATTERY_ENABLE: u8 = 0b0000_1000;
+
+const HOUR_12: u8 = 0b0100_0000;
+
+const OUTPUT_LEVEL: u8 = 0b1000_0000;
+const SQUARE_WAVE_ENABLE: u8 = 0b0100_0000;
+const SQUARE_WAVE_FREQUENCY: u8 = 0b0000_0011;
+
+const TRIMMING_ADD: u8 = 0b1000_0000;
+/// One step of digital trimming in thousandths of ppm (2 cycles per minute)
+const TRIMMING_STEP: i32 = 1017;
+
+const ALARM_POLARITY: u8 = 0b1000_0000;
+const ALARM_MASK: u8 = 0b0111_0000;
+const ALARM_FLAG: u8 = 0b0000_1000;
+
+/// Maximum number of reads while oscillator is stopping, it stops within few
+/// cycles of 32 kHz clock
+const MAX_POLLS: u16 = 1000;
+
+const MIN_YEAR: u16 = 2000;
+const MAX_YEAR: u16 = 2099;
+
+/// Frequency of MFP pin in square wave mode (SQWFS)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SquareWaveFrequency {
+    _1Hz,
+    _4096Hz,
+    _8192Hz,
+    _32768Hz
+}
+
+/// One of two alarms (ALM0, ALM1)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AlarmNumber {
+    Alarm0,
+    Alarm1
+}
+
+/// Alarm with match mode
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Alarm {
+    /// Seconds match
+    Seconds { seconds: u8 },
+    /// Minutes match
+    Minutes { minutes: u8 },
+    /// Hours match
+    Hours { hours: u8 },
+    /// Day of week match
+    Weekday { weekday: Weekday },
+    /// Day of month [1-31] match
+    Date { date: u8 },
+    /// Month, day, hours, minutes and seconds match, year is not compared
+    DateTime { datetime: DateTime }
+}
+
+/// Time of power failure, chip does not record seconds and year
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Timestamp {
+    pub month: u8,
+    pub day: u8,
+    pub hours: u8,
+    pub minutes: u8
+}
+
+/// Times when main supply went down and up again
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PowerFail {
+    pub down: Timestamp,
+    pub up: Timestamp
+}
+
+/// MCP7940N real time clock with 64 bytes of battery backed SRAM
+///
+/// # Example
+///
+/// ```no_run
+/// # use embedded_hal::blocking::i2c::{Write, WriteRead};
+/// # use device_drivers::i2c::rtc::mcp7940n::Timestamp;
+/// # fn example<I2C, E>(i2c: I2C, log_outage: impl Fn(Timestamp, Timestamp))
+/// #     where I2C: Write<Error = E> + WriteRead<Error = E>, E: core::fmt::Debug
+/// # {
+/// use device_drivers::i2c::rtc::mcp7940n::{Rtc, DEFAULT_ADDRESS};
+/// use device_drivers::i2c::rtc::traits::RtcTrait;
+///
+/// let mut rtc = Rtc::new(i2c, DEFAULT_ADDRESS);
+/// rtc.enable_battery(true).unwrap();
+///
+/// if let Some(power_fail) = rtc.get_power_fail().unwrap() {
+///     log_outage(power_fail.down, power_fail.up);
+///     rtc.clear_power_fail().unwrap();
+/// }
+/// # }
+/// ```
+pub struct Rtc<I2CType> {
+    i2c: I2CType,
+    address: u8,
+    hour_mode: HourMode,
+    first_weekday: Weekday
+}
+
+#[derive(Debug)]
+pub enum RtcError<E> {
+    WrongValue,
+    /// Error of i2c bus
+    I2cError(E),
+    /// Oscillator is not started (ST), time is invalid until next `set`
+    OscillatorStopped,
+    /// Oscillator did not stop before time was written
+    Timeout
+}
+
+impl<I2CType, E> Rtc<I2CType>
+    where
+        I2CType: Write<Error = E> + WriteRead<Error = E>
+{
+    pub fn new(i2c: I2CType, address: u8) -> Self {
+        Rtc {
+            i2c,
+            address,
+            hour_mode: HourMode::_24Hour,
+            first_weekday: Weekday::Monday
+        }
+    }
+
+    /// Set mode of hours written to time and alarm registers, each register
+    /// keeps its own 12/24 flag, so reading decodes either mode
+    pub fn hour_mode(mut self, mode: HourMode) -> Self {
+        self.hour_mode = mode;
+        self
+    }
+
+    /// Set weekday stored as 1 in WKDAY register (Monday by default)
+    ///
+    /// Alarms compare weekday registers, so numbering must not change after
+    /// `Alarm::Weekday` is written.
+    pub fn first_weekday(mut self, first: Weekday) -> Self {
+        self.first_weekday = first;
+        self
+    }
+
+    fn weekday_from_register(&self, value: u8) -> Result<Weekday, RtcError<E>> {
+        Weekday::from_number(value & 0x07, self.first_weekday)
+            .ok_or(RtcError::WrongValue)
+    }
+
+    fn alarm_register(number: AlarmNumber) -> u8 {
+        match number {
+            AlarmNumber::Alarm0 => 0x0A,
+            AlarmNumber::Alarm1 => 0x11
+        }
+    }
+
+    fn alarm_enable(number: AlarmNumber) -> u8 {
+        match number {
+            AlarmNumber::Alarm0 => 0b0001_0000,
+            AlarmNumber::Alarm1 => 0b0010_0000
+        }
+    }
+
+    /// Read oscillator start bit (ST), time is valid if oscillator is started
+    pub fn is_time_valid(&mut self) -> Result<bool, RtcError<E>> {
+        Ok(self.read_register(ControlData::Seconds as u8)? & START_OSCILLATOR != 0)
+    }
+
+    /// Enable or disable switching to battery (VBATEN)
+    ///
+    /// Power fail time stamps are recorded only when battery is enabled.
+    pub fn enable_battery(&mut self, enable: bool) -> Result<(), RtcError<E>> {
+        let value = if enable { BATTERY_ENABLE } else { 0_u8 };
+
+        self.update_register(ControlData::Weekday as u8, BATTERY_ENABLE, value)
+    }
+
+    /// Read power fail time stamps, `None` if power did not fail (PWRFAIL)
+    pub fn get_power_fail(&mut self) -> Result<Option<PowerFail>, RtcError<E>> {
+        if self.read_register(ControlData::Weekday as u8)? & POWER_FAIL == 0 {
+            return Ok(None);
+        }
+
+        let mut data = [0_u8; 8];
+
+        self.read_registers(ControlData::PowerDown as u8, &mut data)?;
+
+        let timestamp = |data: &[u8]| Timestamp {
+            month: bcd_to_bin(data[3] & 0x1F),
+            day: bcd_to_bin(data[2] & 0x3F),
+            hours: decode_hours(data[1], data[1] & HOUR_12 != 0),
+            minutes: bcd_to_bin(data[0] & 0x7F)
+        };
+
+        Ok(Some(PowerFail {
+            down: timestamp(&data[..4]),
+            up: timestamp(&data[4..])
+        }))
+    }
+
+    /// Clear power fail flag (PWRFAIL), time stamps are cleared and next
+    /// power failure is recorded
+    pub fn clear_power_fail(&mut self) -> Result<(), RtcError<E>> {
+        self.update_register(ControlData::Weekday as u8, POWER_FAIL, 0_u8)
+    }
+
+    /// Set polarity of alarm output on MFP pin (ALMPOL), shared by both alarms
+    pub fn set_alarm_polarity(&mut self, active_high: bool) -> Result<(), RtcError<E>> {
+        let value = if active_high { ALARM_POLARITY } else { 0_u8 };
+
+        self.update_register(Self::alarm_register(AlarmNumber::Alarm0) + 3, ALARM_POLARITY, value)
+    }
+
+    /// Write alarm and clear its flag, alarm is not enabled
+    ///
+    /// Alarm polarity (ALMPOL) is kept.
+    pub fn set_alarm(&mut self, number: AlarmNumber, alarm: &Alarm) -> Result<(), RtcError<E>> {
+        let (seconds, minutes, hours, weekday, date, month, mask) = match *alarm {
+            Alarm::Seconds { seconds } => (seconds, 0, 0, self.first_weekday, 1, 1, 0b000),
+            Alarm::Minutes { minutes } => (0, minutes, 0, self.first_weekday, 1, 1, 0b001),
+            Alarm::Hours { hours } => (0, 0, hours, self.first_weekday, 1, 1, 0b010),
+            Alarm::Weekday { weekday } => (0, 0, 0, weekday, 1, 1, 0b011),
+            Alarm::Date { date } => (0, 0, 0, self.first_weekday, date, 1, 0b100),
+            Alarm::DateTime { datetime } => (
+                datetime.get_seconds()?,
+                datetime.get_minutes()?,
+                datetime.get_hours()?,
+                datetime.get_weekday()?,
+                datetime.get_day()?,
+                datetime.get_month()?,
+                0b111
+            )
+        };
+
+        // ALMPOL is in ALM0WKDAY only, bit 7 of ALM1WKDAY is unimplemented
+        let polarity = match number {
+            AlarmNumber::Alarm0 => self.read_register(Self::alarm_register(number) + 3)? & ALARM_POLARITY,
+            AlarmNumber::Alarm1 => 0_u8
+        };
+
+        let data = [
+            Self::alarm_register(number),
+            check(seconds, 0, 59)?,
+            check(minutes, 0, 59)?,
+            encode_hours(hours, self.hour_mode, HOUR_12)?,
+            polarity | (mask << 4) | weekday.number_from(self.first_weekday),
+            check(date, 1, 31)?,
+            check(month, 1, 12)?
+        ];
+
+        self.write(&data)
+    }
+
+    /// Read alarm
+    ///
+    /// Year of `Alarm::DateTime` is first year from 2000 where date falls on
+    /// stored weekday.
+    pub fn get_alarm(&mut self, number: AlarmNumber) -> Result<Alarm, RtcError<E>> {
+        let mut data = [0_u8; 6];
+
+        self.read_registers(Self::alarm_register(number), &mut data)?;
+
+        let seconds = bcd_to_bin(data[0] & 0x7F);
+        let minutes = bcd_to_bin(data[1] & 0x7F);
+        let hours = decode_hours(data[2], data[2] & HOUR_12 != 0);
+        let weekday = self.weekday_from_register(data[3])?;
+        let date = bcd_to_bin(data[4] & 0x3F);
+        let month = bcd_to_bin(data[5] & 0x1F);
+
+        match (data[3] & ALARM_MASK) >> 4 {
+            0b000 => Ok(Alarm::Seconds { seconds }),
+            0b001 => Ok(Alarm::Minutes { minutes }),
+            0b010 => Ok(Alarm::Hours { hours }),
+            0b011 => Ok(Alarm::Weekday { weekday }),
+            0b100 => Ok(Alarm::Date { date }),
+            0b111 => {
+                let year = (MIN_YEAR..MIN_YEAR + 28)
+                    .find(|&year| DateTime::from_ymd_hms(year, month, date, hours, minutes, seconds)
+                        .and_then(|datetime| datetime.get_weekday())
+                        .is_ok_and(|found| found == weekday))
+                    .ok_or(RtcError::WrongValue)?;
+
+                Ok(Alarm::DateTime { datetime: DateTime::from_ymd_hms(year, month, date, hours, minutes, seconds)? })
+            },
+            _ => Err(RtcError::WrongValue)
+        }
+    }
+
+    /// Enable or disable alarm (ALMxEN), alarm drives MFP pin unless square
+    /// wave is enabled
+    pub fn enable_alarm(&mut self, number: AlarmNumber, enable: bool) -> Result<(), RtcError<E>> {
+        let bit = Self::alarm_enable(number);
+
+        self.update_register(ControlData::Control as u8, bit, if enable { bit } else { 0_u8 })
+    }
+
+    /// Read alarm flag (ALMxIF)
+    pub fn has_alarm_matched(&mut self, number: AlarmNumber) -> Result<bool, RtcError<E>> {
+        Ok(self.read_register(Self::alarm_register(number) + 3)? & ALARM_FLAG != 0)
+    }
+
+    /// Clear alarm flag (ALMxIF)
+    pub fn clear_alarm_matched(&mut self, number: AlarmNumber) -> Result<(), RtcError<E>> {
+        self.update_register(Self::alarm_register(number) + 3, ALARM_FLAG, 0_u8)
+    }
+
+    /// Read digital trimming, number of clock cycles added per minute is
+    /// twice the value
+    pub fn get_trimming(&mut self) -> Result<i8, RtcError<E>> {
+        let value = self.read_register(ControlData::Trimming as u8)?;
+        let trimming = (value & 0x7F) as i8;
+
+        match value & TRIMMING_ADD {
+            0 => Ok(-trimming),
+            _ => Ok(trimming)
+        }
+    }
+
+    /// Write digital trimming [-127-127], positive value speeds clock up
+    ///
+    /// One step is 2 clock cycles per minute, about 1.017 ppm.
+    pub fn set_trimming(&mut self, trimming: i8) -> Result<(), RtcError<E>> {
+        if trimming == i8::MIN { return Err(RtcError::WrongValue); }
+
+        let sign = if trimming > 0 { TRIMMING_ADD } else { 0_u8 };
+
+        self.write_register(ControlData::Trimming as u8, sign | trimming.unsigned_abs())
+    }
+
+    /// Output square wave on MFP pin (SQWEN = 1)
+    pub fn set_square_wave(&mut self, frequency: SquareWaveFrequency) -> Result<(), RtcError<E>> {
+        let rate = match frequency {
+            SquareWaveFrequency::_1Hz => 0b00,
+            SquareWaveFrequency::_4096Hz => 0b01,
+            SquareWaveFrequency::_8192Hz => 0b10,
+            SquareWaveFrequency::_32768Hz => 0b11
+        };
+
+        self.update_register(ControlData::Control as u8, SQUARE_WAVE_ENABLE | SQUARE_WAVE_FREQUENCY,
+                             SQUARE_WAVE_ENABLE | rate)
+    }
+
+    /// Disable square wave (SQWEN = 0), MFP pin is driven with `level` when
+    /// alarms are disabled
+    pub fn disable_square_wave(&mut self, level: bool) -> Result<(), RtcError<E>> {
+        let value = if level { OUTPUT_LEVEL } else { 0_u8 };
+
+        self.update_register(ControlData::Control as u8, SQUARE_WAVE_ENABLE | OUTPUT_LEVEL, value)
+    }
+
+    /// Read battery backed SRAM
+    ///
+    /// # Arguments
+    ///
+    /// * `offset` - first byte of SRAM [0-63]
+    /// * `data` - buffer, must fit in SRAM from offset
+    pub fn read_ram(&mut self, offset: u8, data: &mut [u8]) -> Result<(), RtcError<E>> {
+        if offset as usize + data.len() > RAM_SIZE { return Err(RtcError::WrongValue); }
+
+        self.read_registers(ControlData::Ram as u8 + offset, data)
+    }
+
+    /// Write battery backed SRAM
+    ///
+    /// # Arguments
+    ///
+    /// * `offset` - first byte of SRAM [0-63]
+    /// * `data` - bytes to write, must fit in SRAM from offset
+    pub fn write_ram(&mut self, offset: u8, data: &[u8]) -> Result<(), RtcError<E>> {
+        if offset as usize + data.len() > RAM_SIZE { return Err(RtcError::WrongValue); }
+
+        let mut buffer = [0_u8; RAM_SIZE + 1];
+        buffer[0] = ControlData::Ram as u8 + offset;
+        buffer[1..=data.len()].copy_from_slice(data);
+
+        self.write(&buffer[..=data.len()])
+    }
+}
+
+impl_registers!();
+
+impl<E> ErrorTrait for RtcError<E>
+    where
+        E: Debug
+{
+    fn kind(&self) -> ErrorKind {
+        match self {
+            RtcError::WrongValue => ErrorKind::WrongValue,
+            RtcError::I2cError(_) => ErrorKind::Bus,
+            RtcError::OscillatorStopped => ErrorKind::TimeInvalid,
+            RtcError::Timeout => ErrorKind::Timeout
+        }
+    }
+}
+
+impl<I2CType, E> RtcTrait<DateTime> for Rtc<I2CType>
+    where
+        I2CType: Write<Error = E> + WriteRead<Error = E>,
+        E: Debug
+{
+    type Error = RtcError<E>;
+
+    /// Write time and start oscillator, years [2000-2099] are supported
+    ///
+    /// Oscillator is stopped while time is written, as datasheet requires.
+    /// Battery enable bit and power fail flag with its time stamps are kept.
+    fn set(&mut self, datetime: &DateTime) -> Result<(), Self::Error> {
+        let year = datetime.get_year()?;
+
+        if !(MIN_YEAR..=MAX_YEAR).contains(&year) { return Err(RtcError::WrongValue); }
+
+        let seconds = bin_to_bcd(datetime.get_seconds()?);
+        let mut data_to_send = [
+            ControlData::Seconds as u8,
+            seconds,
+            bin_to_bcd(datetime.get_minutes()?),
+            encode_hours(datetime.get_hours()?, self.hour_mode, HOUR_12)?,
+            datetime.get_weekday()?.number_from(self.first_weekday),
+            bin_to_bcd(datetime.get_day()?),
+            bin_to_bcd(datetime.get_month()?),
+            bin_to_bcd((year - MIN_YEAR) as u8)
+        ];
+
+        self.update_register(ControlData::Seconds as u8, START_OSCILLATOR, 0_u8)?;
+
+        let mut weekday = self.read_register(ControlData::Weekday as u8)?;
+        let mut polls = 0;
+
+        while weekday & OSCILLATOR_RUNNING != 0 {
+            polls += 1;
+            if polls > MAX_POLLS {
+                // restart oscillator, time keeps running unchanged
+                self.update_register(ControlData::Seconds as u8, START_OSCILLATOR, START_OSCILLATOR)?;
+
+                return Err(RtcError::Timeout);
+            }
+
+            weekday = self.read_register(ControlData::Weekday as u8)?;
+        }
+
+        // PWRFAIL is written as read, writing 0 would clear time stamps
+        data_to_send[4] |= weekday & (BATTERY_ENABLE | POWER_FAIL);
+
+        self.write(&data_to_send)?;
+
+        self.write_register(ControlData::Seconds as u8, START_OSCILLATOR | seconds)
+    }
+
+    /// Read time, fails with `OscillatorStopped` if time is invalid
+    fn get(&mut self) -> Result<DateTime, Self::Error> {
+        let mut data_to_read = [0_u8; 7];
+
+        self.read_registers(ControlData::Seconds as u8, &mut data_to_read)?;
+
+        if data_to_read[0] & START_OSCILLATOR == 0 {
+            return Err(RtcError::OscillatorStopped);
+        }
+
+        Ok(
+            DateTime::from_ymd_hms(
+                MIN_YEAR + (bcd_to_bin(data_to_read[6]) as u16),
+                bcd_to_bin(data_to_read[5] & 0x1F),
+                bcd_to_bin(data_to_read[4] & 0x3F),
+                decode_hours(data_to_read[2], data_to_read[2] & HOUR_12 != 0),
+                bcd_to_bin(data_to_read[1] & 0x7F),
+                bcd_to_bin(data_to_read[0] & 0x7F)
+            )?
+        )
+    }
+}
+
+/// Alarm 0 is used
+impl<I2CType, E> AlarmTrait for Rtc<I2CType>
+    where
+        I2CType: Write<Error = E> + WriteRead<Error = E>,
+        E: Debug
+{
+    type Alarm = Alarm;
+
+    fn set_alarm(&mut self, alarm: &Alarm) -> Result<(), Self::Error> {
+        Rtc::set_alarm(self, AlarmNumber::Alarm0, alarm)
+    }
+
+    fn get_alarm(&mut self) -> Result<Alarm, Self::Error> {
+        Rtc::get_alarm(self, AlarmNumber::Alarm0)
+    }
+
+    fn enable_alarm_interrupt(&mut self, enable: bool) -> Result<(), Self::Error> {
+        self.enable_alarm(AlarmNumber::Alarm0, enable)
+    }
+
+    fn has_alarm_matched(&mut self) -> Result<bool, Self::Error> {
+        Rtc::has_alarm_matched(self, AlarmNumber::Alarm0)
+    }
+
+    fn clear_alarm_matched(&mut self) -> Result<(), Self::Error> {
+        Rtc::clear_alarm_matched(self, AlarmNumber::Alarm0)
+    }
+}
+
+impl<I2CType, E> SquareWaveTrait for Rtc<I2CType>
+    where
+        I2CType: Write<Error = E> + WriteRead<Error = E>,
+        E: Debug
+{
+    type Frequency = SquareWaveFrequency;
+
+    fn set_square_wave(&mut self, frequency: SquareWaveFrequency) -> Result<(), Self::Error> {
+        Rtc::set_square_wave(self, frequency)
+    }
+
+    fn disable_square_wave(&mut self) -> Result<(), Self::Error> {
+        Rtc::disable_square_wave(self, false)
+    }
+}
+
+/// Digital trimming is used, one step is about 1 ppm
+impl<I2CType, E> CalibrationTrait for Rtc<I2CType>
+    where
+        I2CType: Write<Error = E> + WriteRead<Error = E>,
+        E: Debug
+{
+    fn get_correction(&mut self) -> Result<i32, Self::Error> {
+        let trimming = self.get_trimming()? as i32;
+
+        Ok((trimming * TRIMMING_STEP + trimming.signum() * 50) / 100)
+    }
+
+    fn set_correction(&mut self, correction: i32) -> Result<(), Self::Error> {
+        // i64, scaling of large correction overflows i32
+        let step = TRIMMING_STEP as i64;
+        let trimming = (correction as i64 * 100 + correction.signum() as i64 * step / 2) / step;
+
+        self.set_trimming(i8::try_from(trimming).map_err(|_| RtcError::WrongValue)?)
+    }
+}
+
+impl<I2CType, E> NvramTrait for Rtc<I2CType>
+    where
+        I2CType: Write<Error = E> + WriteRead<Error = E>,
+        E: Debug
+{
+    fn nvram_size(&self) -> usize {
+        RAM_SIZE
+    }
+
+    fn read_nvram(&mut self, offset: u8, data: &mut [u8]) -> Result<(), Self::Error> {
+        self.read_ram(offset, data)
+    }
+
+    fn write_nvram(&mut self, offset: u8, data: &[u8]) -> Result<(), Self::Error> {
+        self.write_ram(offset, data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i2c::mock::{Device, DumpI2c};
+
+    #[derive(Default)]
+    struct Mcp7940n {
+        /// Reads of weekday register until OSCRUN follows ST
+        oscillator_delay: u16
+    }
+
+    impl Device for Mcp7940n {
+        fn start(&mut self, registers: &mut [u8], bytes: &[u8]) -> Result<(), ()> {
+            if bytes[0] == 0x00 && bytes.len() > 2 {
+                assert_eq!(registers[0x03] & OSCILLATOR_RUNNING, 0, "time written while running");
+                assert_eq!(bytes[1] & START_OSCILLATOR, 0);
+            }
+
+            Ok(())
+        }
+
+        fn read(&mut self, registers: &mut [u8], pointer: usize) -> u8 {
+            if pointer == 0x03 {
+                match self.oscillator_delay {
+                    0 => {
+                        let running = if registers[0x00] & START_OSCILLATOR != 0 { OSCILLATOR_RUNNING } else { 0 };
+                        registers[0x03] = (registers[0x03] & !OSCILLATOR_RUNNING) | running;
+                    },
+                    _ => self.oscillator_delay -= 1
+                }
+            }
+
+            registers[pointer]
+        }
+    }
+
+    fn rtc() -> Rtc<DumpI2c<Mcp7940n>> {
+        Rtc::new(DumpI2c::with_device(DEFAULT_ADDRESS, 0x60, Mcp7940n::default()), DEFAULT_ADDRESS)
+    }
+
+    #[test]
+    fn test_time() {
+        let mut rtc = rtc();
+
+        assert!(matches!(rtc.get(), Err(RtcError::OscillatorStopped)));
+
+        rtc.enable_battery(true).unwrap();
+        let datetime = DateTime::from_ymd_hms(2021, 3, 14, 15, 9, 26).unwrap();
+        rtc.set(&datetime).unwrap();
+        assert_eq!(rtc.i2c.registers[0x00..0x07], [0xA6, 0x09, 0x15, 0x0F, 0x14, 0x03, 0x21]);
+        assert!(rtc.is_time_valid().unwrap());
+        assert_eq!(rtc.get().unwrap(), datetime);
+    }
+
+    #[test]
+    fn test_set_running() {
+        let mut rtc = rtc();
+        rtc.i2c.registers[0x00] = START_OSCILLATOR;
+        rtc.i2c.registers[0x03] = OSCILLATOR_RUNNING | POWER_FAIL | BATTERY_ENABLE;
+        rtc.i2c.registers[0x18] = 0x59;
+        rtc.i2c.device.oscillator_delay = 5;
+
+        let datetime = DateTime::from_ymd_hms(2021, 3, 14, 15, 9, 26).unwrap();
+        rtc.set(&datetime).unwrap();
+        assert_eq!(rtc.i2c.registers[0x00], START_OSCILLATOR | 0x26);
+        assert_eq!(rtc.i2c.registers[0x03] & (POWER_FAIL | BATTERY_ENABLE), POWER_FAIL | BATTERY_ENABLE);
+        assert_eq!(rtc.get_power_fail().unwrap().unwrap().down.minutes, 59);
+
+        rtc.i2c.device.oscillator_delay = MAX_POLLS + 1;
+        assert!(matches!(rtc.set(&datetime), Err(RtcError::Timeout)));
+        assert_ne!(rtc.i2c.registers[0x00] & START_OSCILLATOR, 0);
+    }
+
+    #[test]
+    fn test_power_fail() {
+        let mut rtc = rtc();
+
+        assert_eq!(rtc.get_power_fail().unwrap(), None);
+
+        rtc.i2c.registers[0x03] = POWER_FAIL | BATTERY_ENABLE | 0x07;
+        rtc.i2c.registers[0x18..0x20].copy_from_slice(&[0x59, 0x23, 0x13, 0x63, 0x05, 0x01, 0x14, 0x03]);
+        assert_eq!(rtc.get_power_fail().unwrap(), Some(PowerFail {
+            down: Timestamp { month: 3, day: 13, hours: 23, minutes: 59 },
+            up: Timestamp { month: 3, day: 14, hours: 1, minutes: 5 }
+        }));
+
+        rtc.clear_power_fail().unwrap();
+        assert_eq!(rtc.i2c.registers[0x03], BATTERY_ENABLE | 0x07);
+    }
+
+    #[test]
+    fn test_alarm() {
+        let mut rtc = rtc();
+
+        let alarm = Alarm::Weekday { weekday: Weekday::Friday };
+        rtc.set_alarm(AlarmNumber::Alarm1, &alarm).unwrap();
+        assert_eq!(rtc.i2c.registers[0x14], 0x35);
+        assert_eq!(rtc.get_alarm(AlarmNumber::Alarm1).unwrap(), alarm);
+
+        let datetime = DateTime::from_ymd_hms(2021, 3, 14, 15, 9, 26).unwrap();
+        rtc.set_alarm(AlarmNumber::Alarm0, &Alarm::DateTime { datetime }).unwrap();
+        match rtc.get_alarm(AlarmNumber::Alarm0).unwrap() {
+            Alarm::DateTime { datetime: found } => {
+                assert_eq!(found.get_weekday().unwrap(), Weekday::Sunday);
+                assert_eq!((found.get_month().unwrap(), found.get_day().unwrap()), (3, 14));
+            },
+            alarm => panic!("{:?}", alarm)
+        }
+
+        rtc.set_alarm_polarity(true).unwrap();
+        rtc.set_alarm(AlarmNumber::Alarm0, &Alarm::Minutes { minutes: 5 }).unwrap();
+        assert_eq!(rtc.i2c.registers[0x0D], ALARM_POLARITY | 0x11);
+
+        rtc.i2c.registers[0x14] = ALARM_POLARITY;
+        rtc.set_alarm(AlarmNumber::Alarm1, &Alarm::Minutes { minutes: 5 }).unwrap();
+        assert_eq!(rtc.i2c.registers[0x14], 0x11);
+
+        rtc.enable_alarm(AlarmNumber::Alarm1, true).unwrap();
+        assert_eq!(rtc.i2c.registers[0x07], 0x20);
+
+        rtc.i2c.registers[0x14] |= ALARM_FLAG;
+        assert!(rtc.has_alarm_matched(AlarmNumber::Alarm1).unwrap());
+        rtc.clear_alarm_matched(AlarmNumber::Alarm1).unwrap();
+        assert!(!rtc.has_alarm_matched(AlarmNumber::Alarm1).unwrap());
+    }
+
+    #[test]
+    fn test_trimming_and_ram() {
+        let mut rtc = rtc();
+
+        rtc.set_trimming(-5).unwrap();
+        assert_eq!(rtc.i2c.registers[0x08], 0x05);
+        rtc.set_trimming(5).unwrap();
+        assert_eq!(rtc.i2c.registers[0x08], 0x85);
+        assert_eq!(rtc.get_trimming().unwrap(), 5);
+
+        rtc.write_ram(62, &[0xAA, 0x55]).unwrap();
+        assert_eq!(rtc.i2c.registers[0x5E..0x60], [0xAA, 0x55]);
+        assert!(rtc.write_ram(63, &[0, 0]).is_err());
+    }
+
+    #[test]
+    fn test_traits() {
+        let mut rtc = rtc();
+
+        rtc.set_correction(-51).unwrap();
+        assert_eq!(rtc.i2c.registers[0x08], 0x05);
+        assert_eq!(rtc.get_correction().unwrap(), -51);
+        assert_eq!(rtc.set_correction(1300).unwrap_err().kind(), ErrorKind::WrongValue);
+        assert_eq!(rtc.set_correction(i32::MAX).unwrap_err().kind(), ErrorKind::WrongValue);
+        assert_eq!(rtc.set_correction(i32::MIN).unwrap_err().kind(), ErrorKind::WrongValue);
+
+        assert_eq!(rtc.nvram_size(), RAM_SIZE);
+        assert_eq!(rtc.get().unwrap_err().kind(), ErrorKind::TimeInvalid);
+    }
+}
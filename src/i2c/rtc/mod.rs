@@ -1,4 +1,13 @@
 
 pub mod ds3231;
+pub mod ds1307;
+pub mod pcf8563;
+pub mod pcf85063a;
+pub mod rv3028;
+pub mod mcp7940n;
 pub mod traits;
 pub mod datetime;
+pub mod duration;
+pub mod timezone;
+
+mod common;
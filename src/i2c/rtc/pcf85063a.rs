@@ -0,0 +1,465 @@
+use core::fmt::Debug;
+use embedded_hal::blocking::{i2c::{Write, WriteRead}};
+use super::traits::{RtcTrait, ErrorTrait, ErrorKind, AlarmTrait, SquareWaveTrait};
+use super::datetime::{DateTime, Weekday};
+use crate::i2c::rtc::traits::DateTimeTrait;
+use super::common::{Registers, impl_registers, bcd_to_bin, bin_to_bcd, check, decode_hours, encode_hours};
+
+pub use super::common::HourMode;
+
+/// Address of PCF85063A
+pub const DEFAULT_ADDRESS: u8 = 0x51;
+
+#[repr(u8)]
+enum ControlData {
+    Control1 = 0x00,
+    Control2 = 0x01,
+    Seconds = 0x04,
+    Alarm = 0x0B,
+    TimerValue = 0x10,
+    TimerMode = 0x11
+}
+
+const HOUR_12: u8 = 0b0000_0010;
+
+const ALARM_INTERRUPT_ENABLE: u8 = 0b1000_0000;
+const ALARM_FLAG: u8 = 0b0100_0000;
+const TIMER_FLAG: u8 = 0b0000_1000;
+const CLKOUT_FREQUENCY: u8 = 0b0000_0111;
+
+const TIMER_ENABLE: u8 = 0b0000_0100;
+const TIMER_INTERRUPT_ENABLE: u8 = 0b0000_0010;
+
+const OSCILLATOR_STOP: u8 = 0b1000_0000;
+const ALARM_DISABLE: u8 = 0b1000_0000;
+
+const MIN_YEAR: u16 = 2000;
+const MAX_YEAR: u16 = 2099;
+
+/// Frequency of CLKOUT pin (COF)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ClkoutFrequency {
+    _32768Hz,
+    _16384Hz,
+    _8192Hz,
+    _4096Hz,
+    _2048Hz,
+    _1024Hz,
+    _1Hz
+}
+
+/// Source clock of countdown timer (TCF)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TimerFrequency {
+    _4096Hz,
+    _64Hz,
+    _1Hz,
+    /// One tick per minute
+    _1_60Hz
+}
+
+/// Alarm, fields set to `None` are not compared
+///
+/// Alarm fires when all set fields match.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Alarm {
+    pub seconds: Option<u8>,
+    pub minutes: Option<u8>,
+    pub hours: Option<u8>,
+    /// Day of month [1-31]
+    pub day: Option<u8>,
+    pub weekday: Option<Weekday>
+}
+
+/// PCF85063A low power real time clock
+///
+/// # Example
+///
+/// ```no_run
+/// # use embedded_hal::blocking::i2c::{Write, WriteRead};
+/// # use device_drivers::i2c::rtc::datetime::DateTime;
+/// # fn example<I2C, E>(i2c: I2C, build_time: DateTime)
+/// #     where I2C: Write<Error = E> + WriteRead<Error = E>, E: core::fmt::Debug
+/// # {
+/// use device_drivers::i2c::rtc::pcf85063a::{Rtc, DEFAULT_ADDRESS, Alarm};
+/// use device_drivers::i2c::rtc::traits::RtcTrait;
+///
+/// let mut rtc = Rtc::new(i2c, DEFAULT_ADDRESS);
+/// if !rtc.is_time_valid().unwrap() {
+///     rtc.set(&build_time).unwrap();
+/// }
+///
+/// rtc.set_alarm(&Alarm { hours: Some(7), minutes: Some(0), seconds: Some(0), ..Alarm::default() }).unwrap();
+/// rtc.enable_alarm_interrupt(true).unwrap();
+/// # }
+/// ```
+pub struct Rtc<I2CType> {
+    i2c: I2CType,
+    address: u8,
+    hour_mode: HourMode,
+    first_weekday: Weekday
+}
+
+#[derive(Debug)]
+pub enum RtcError<E> {
+    WrongValue,
+    /// Error of i2c bus
+    I2cError(E),
+    /// Oscillator was stopped (OS), time is invalid until next `set`
+    OscillatorStopped
+}
+
+impl<I2CType, E> Rtc<I2CType>
+    where
+        I2CType: Write<Error = E> + WriteRead<Error = E>
+{
+    pub fn new(i2c: I2CType, address: u8) -> Self {
+        Rtc {
+            i2c,
+            address,
+            hour_mode: HourMode::_24Hour,
+            first_weekday: Weekday::Sunday
+        }
+    }
+
+    /// Set mode of hours, `set` writes it to 12_24 bit of Control_1
+    ///
+    /// Alarm hours are encoded in the same mode.
+    pub fn hour_mode(mut self, mode: HourMode) -> Self {
+        self.hour_mode = mode;
+        self
+    }
+
+    /// Set weekday stored as 0 in Weekdays register (Sunday by default), also
+    /// used for weekday of `Alarm`
+    pub fn first_weekday(mut self, first: Weekday) -> Self {
+        self.first_weekday = first;
+        self
+    }
+
+    /// Write control 2, flags are written as 1 (no change) unless cleared
+    ///
+    /// Writing back flags as read could clear flag set in between.
+    fn update_control2(&mut self, mask: u8, value: u8, clear: u8) -> Result<(), RtcError<E>> {
+        let current = self.read_register(ControlData::Control2 as u8)?;
+        let value = (current & !mask) | (value & mask) | ALARM_FLAG | TIMER_FLAG;
+
+        self.write_register(ControlData::Control2 as u8, value & !clear)
+    }
+
+    fn weekday_to_register(&self, weekday: Weekday) -> u8 {
+        weekday.number_from(self.first_weekday) - 1
+    }
+
+    fn weekday_from_register(&self, value: u8) -> Result<Weekday, RtcError<E>> {
+        Weekday::from_number((value & 0x07) + 1, self.first_weekday)
+            .ok_or(RtcError::WrongValue)
+    }
+
+    /// Read oscillator stop flag (OS), time is valid if flag is not set
+    pub fn is_time_valid(&mut self) -> Result<bool, RtcError<E>> {
+        Ok(self.read_register(ControlData::Seconds as u8)? & OSCILLATOR_STOP == 0)
+    }
+
+    /// Write alarm, interrupt is not changed
+    ///
+    /// Hours are written in mode set by `hour_mode`, it must match mode of chip.
+    pub fn set_alarm(&mut self, alarm: &Alarm) -> Result<(), RtcError<E>> {
+        let field = |value: Option<u8>, min: u8, max: u8| match value {
+            Some(value) => check(value, min, max),
+            None => Ok(ALARM_DISABLE)
+        };
+
+        let data = [
+            ControlData::Alarm as u8,
+            field(alarm.seconds, 0, 59)?,
+            field(alarm.minutes, 0, 59)?,
+            match alarm.hours {
+                Some(hours) => encode_hours(hours, self.hour_mode, 0_u8)?,
+                None => ALARM_DISABLE
+            },
+            field(alarm.day, 1, 31)?,
+            field(alarm.weekday.map(|weekday| self.weekday_to_register(weekday)), 0, 6)?
+        ];
+
+        self.write(&data)
+    }
+
+    /// Read alarm
+    pub fn get_alarm(&mut self) -> Result<Alarm, RtcError<E>> {
+        let hour_12 = self.read_register(ControlData::Control1 as u8)? & HOUR_12 != 0;
+        let mut data = [0_u8; 5];
+
+        self.read_registers(ControlData::Alarm as u8, &mut data)?;
+
+        let enabled = |value: u8| value & ALARM_DISABLE == 0;
+        let field = |value: u8, mask: u8| match enabled(value) {
+            true => Some(bcd_to_bin(value & mask)),
+            false => None
+        };
+
+        Ok(Alarm {
+            seconds: field(data[0], 0x7F),
+            minutes: field(data[1], 0x7F),
+            hours: match enabled(data[2]) {
+                true => Some(decode_hours(data[2], hour_12)),
+                false => None
+            },
+            day: field(data[3], 0x3F),
+            weekday: match field(data[4], 0x07) {
+                Some(value) => Some(self.weekday_from_register(value)?),
+                None => None
+            }
+        })
+    }
+
+    /// Enable or disable alarm interrupt (AIE) on INT pin
+    pub fn enable_alarm_interrupt(&mut self, enable: bool) -> Result<(), RtcError<E>> {
+        let value = if enable { ALARM_INTERRUPT_ENABLE } else { 0_u8 };
+
+        self.update_control2(ALARM_INTERRUPT_ENABLE, value, 0_u8)
+    }
+
+    /// Read alarm flag (AF)
+    pub fn has_alarm_matched(&mut self) -> Result<bool, RtcError<E>> {
+        Ok(self.read_register(ControlData::Control2 as u8)? & ALARM_FLAG != 0)
+    }
+
+    /// Clear alarm flag (AF), releases INT pin
+    pub fn clear_alarm_matched(&mut self) -> Result<(), RtcError<E>> {
+        self.update_control2(0_u8, 0_u8, ALARM_FLAG)
+    }
+
+    /// Start countdown timer, timer interrupt is not changed
+    ///
+    /// # Arguments
+    ///
+    /// * `frequency` - source clock of timer
+    /// * `value` - number of ticks until timer flag is set [1-255]
+    pub fn set_timer(&mut self, frequency: TimerFrequency, value: u8) -> Result<(), RtcError<E>> {
+        if value == 0 { return Err(RtcError::WrongValue); }
+
+        let source = match frequency {
+            TimerFrequency::_4096Hz => 0b00,
+            TimerFrequency::_64Hz => 0b01,
+            TimerFrequency::_1Hz => 0b10,
+            TimerFrequency::_1_60Hz => 0b11
+        };
+
+        let mode = self.read_register(ControlData::TimerMode as u8)? & TIMER_INTERRUPT_ENABLE;
+
+        self.write(&[ControlData::TimerValue as u8, value, (source << 3) | TIMER_ENABLE | mode])
+    }
+
+    /// Stop countdown timer, source clock is set to 1/60 Hz to save power
+    pub fn disable_timer(&mut self) -> Result<(), RtcError<E>> {
+        self.update_register(ControlData::TimerMode as u8, 0b0001_1100, 0b0001_1000)
+    }
+
+    /// Enable or disable timer interrupt (TIE) on INT pin
+    pub fn enable_timer_interrupt(&mut self, enable: bool) -> Result<(), RtcError<E>> {
+        let value = if enable { TIMER_INTERRUPT_ENABLE } else { 0_u8 };
+
+        self.update_register(ControlData::TimerMode as u8, TIMER_INTERRUPT_ENABLE, value)
+    }
+
+    /// Read timer flag (TF)
+    pub fn has_timer_expired(&mut self) -> Result<bool, RtcError<E>> {
+        Ok(self.read_register(ControlData::Control2 as u8)? & TIMER_FLAG != 0)
+    }
+
+    /// Clear timer flag (TF), releases INT pin
+    pub fn clear_timer_expired(&mut self) -> Result<(), RtcError<E>> {
+        self.update_control2(0_u8, 0_u8, TIMER_FLAG)
+    }
+
+    /// Output clock on CLKOUT pin
+    pub fn set_clkout(&mut self, frequency: ClkoutFrequency) -> Result<(), RtcError<E>> {
+        self.update_control2(CLKOUT_FREQUENCY, frequency as u8, 0_u8)
+    }
+
+    /// Disable CLKOUT pin, pin is driven low
+    pub fn disable_clkout(&mut self) -> Result<(), RtcError<E>> {
+        self.update_control2(CLKOUT_FREQUENCY, CLKOUT_FREQUENCY, 0_u8)
+    }
+}
+
+impl_registers!();
+
+impl<E> ErrorTrait for RtcError<E>
+    where
+        E: Debug
+{
+    fn kind(&self) -> ErrorKind {
+        match self {
+            RtcError::WrongValue => ErrorKind::WrongValue,
+            RtcError::I2cError(_) => ErrorKind::Bus,
+            RtcError::OscillatorStopped => ErrorKind::TimeInvalid
+        }
+    }
+}
+
+impl<I2CType, E> RtcTrait<DateTime> for Rtc<I2CType>
+    where
+        I2CType: Write<Error = E> + WriteRead<Error = E>,
+        E: Debug
+{
+    type Error = RtcError<E>;
+
+    /// Write time and hour mode, clears oscillator stop flag
+    ///
+    /// Years [2000-2099] are supported.
+    fn set(&mut self, datetime: &DateTime) -> Result<(), Self::Error> {
+        let year = datetime.get_year()?;
+
+        if !(MIN_YEAR..=MAX_YEAR).contains(&year) { return Err(RtcError::WrongValue); }
+
+        let data_to_send = [
+            ControlData::Seconds as u8,
+            bin_to_bcd(datetime.get_seconds()?),
+            bin_to_bcd(datetime.get_minutes()?),
+            encode_hours(datetime.get_hours()?, self.hour_mode, 0_u8)?,
+            bin_to_bcd(datetime.get_day()?),
+            self.weekday_to_register(datetime.get_weekday()?),
+            bin_to_bcd(datetime.get_month()?),
+            bin_to_bcd((year - MIN_YEAR) as u8)
+        ];
+
+        let hour_12 = match self.hour_mode {
+            HourMode::_24Hour => 0_u8,
+            HourMode::_12Hour => HOUR_12
+        };
+
+        self.update_register(ControlData::Control1 as u8, HOUR_12, hour_12)?;
+
+        self.write(&data_to_send)
+    }
+
+    /// Read time, fails with `OscillatorStopped` if time is invalid
+    fn get(&mut self) -> Result<DateTime, Self::Error> {
+        let mut data_to_read = [0_u8; ControlData::Seconds as usize + 7];
+
+        self.read_registers(ControlData::Control1 as u8, &mut data_to_read)?;
+
+        let hour_12 = data_to_read[ControlData::Control1 as usize] & HOUR_12 != 0;
+        let time = &data_to_read[ControlData::Seconds as usize..];
+
+        if time[0] & OSCILLATOR_STOP != 0 {
+            return Err(RtcError::OscillatorStopped);
+        }
+
+        Ok(
+            DateTime::from_ymd_hms(
+                MIN_YEAR + (bcd_to_bin(time[6]) as u16),
+                bcd_to_bin(time[5] & 0x1F),
+                bcd_to_bin(time[3] & 0x3F),
+                decode_hours(time[2], hour_12),
+                bcd_to_bin(time[1] & 0x7F),
+                bcd_to_bin(time[0] & 0x7F)
+            )?
+        )
+    }
+}
+
+impl<I2CType, E> AlarmTrait for Rtc<I2CType>
+    where
+        I2CType: Write<Error = E> + WriteRead<Error = E>,
+        E: Debug
+{
+    type Alarm = Alarm;
+
+    fn set_alarm(&mut self, alarm: &Alarm) -> Result<(), Self::Error> {
+        Rtc::set_alarm(self, alarm)
+    }
+
+    fn get_alarm(&mut self) -> Result<Alarm, Self::Error> {
+        Rtc::get_alarm(self)
+    }
+
+    fn enable_alarm_interrupt(&mut self, enable: bool) -> Result<(), Self::Error> {
+        Rtc::enable_alarm_interrupt(self, enable)
+    }
+
+    fn has_alarm_matched(&mut self) -> Result<bool, Self::Error> {
+        Rtc::has_alarm_matched(self)
+    }
+
+    fn clear_alarm_matched(&mut self) -> Result<(), Self::Error> {
+        Rtc::clear_alarm_matched(self)
+    }
+}
+
+impl<I2CType, E> SquareWaveTrait for Rtc<I2CType>
+    where
+        I2CType: Write<Error = E> + WriteRead<Error = E>,
+        E: Debug
+{
+    type Frequency = ClkoutFrequency;
+
+    fn set_square_wave(&mut self, frequency: ClkoutFrequency) -> Result<(), Self::Error> {
+        self.set_clkout(frequency)
+    }
+
+    fn disable_square_wave(&mut self) -> Result<(), Self::Error> {
+        self.disable_clkout()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i2c::mock::DumpI2c;
+
+    fn rtc() -> Rtc<DumpI2c> {
+        let mut i2c = DumpI2c::new(DEFAULT_ADDRESS, 0x12);
+        i2c.registers[ControlData::Seconds as usize] = OSCILLATOR_STOP;
+
+        Rtc::new(i2c, DEFAULT_ADDRESS)
+    }
+
+    #[test]
+    fn test_time() {
+        let mut rtc = rtc().hour_mode(HourMode::_12Hour);
+
+        assert!(matches!(rtc.get(), Err(RtcError::OscillatorStopped)));
+
+        let datetime = DateTime::from_ymd_hms(2021, 3, 14, 15, 9, 26).unwrap();
+        rtc.set(&datetime).unwrap();
+        assert_eq!(rtc.i2c.registers[0x00], HOUR_12);
+        assert_eq!(rtc.i2c.registers[0x04..0x0B], [0x26, 0x09, 0x23, 0x14, 0x00, 0x03, 0x21]);
+        assert!(rtc.is_time_valid().unwrap());
+        assert_eq!(rtc.get().unwrap(), datetime);
+
+        assert!(rtc.set(&DateTime::from_ymd_hms(2100, 1, 1, 0, 0, 0).unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_alarm() {
+        let mut rtc = rtc();
+
+        let alarm = Alarm { seconds: Some(0), minutes: Some(30), day: Some(1), ..Alarm::default() };
+        rtc.set_alarm(&alarm).unwrap();
+        assert_eq!(rtc.i2c.registers[0x0B..0x10], [0x00, 0x30, 0x80, 0x01, 0x80]);
+        assert_eq!(rtc.get_alarm().unwrap(), alarm);
+
+        rtc.i2c.registers[0x01] = ALARM_FLAG | TIMER_FLAG;
+        rtc.enable_alarm_interrupt(true).unwrap();
+        rtc.clear_alarm_matched().unwrap();
+        assert_eq!(rtc.i2c.registers[0x01], ALARM_INTERRUPT_ENABLE | TIMER_FLAG);
+    }
+
+    #[test]
+    fn test_timer_and_clkout() {
+        let mut rtc = rtc();
+
+        rtc.enable_timer_interrupt(true).unwrap();
+        rtc.set_timer(TimerFrequency::_64Hz, 32).unwrap();
+        assert_eq!(rtc.i2c.registers[0x10..0x12], [32, 0x0E]);
+
+        rtc.disable_timer().unwrap();
+        assert_eq!(rtc.i2c.registers[0x11], 0x1A);
+
+        rtc.set_clkout(ClkoutFrequency::_1Hz).unwrap();
+        assert_eq!(rtc.i2c.registers[0x01] & CLKOUT_FREQUENCY, 0b110);
+    }
+}
@@ -0,0 +1,431 @@
+use core::fmt::Debug;
+use embedded_hal::blocking::{i2c::{Write, WriteRead}};
+use super::traits::{RtcTrait, ErrorTrait, ErrorKind, AlarmTrait, SquareWaveTrait};
+use super::datetime::{DateTime, Weekday};
+use crate::i2c::rtc::traits::DateTimeTrait;
+use super::common::{Registers, impl_registers, bcd_to_bin, bin_to_bcd, check};
+
+/// Address of PCF8563
+pub const DEFAULT_ADDRESS: u8 = 0x51;
+
+#[repr(u8)]
+enum ControlData {
+    Control2 = 0x01,
+    Seconds = 0x02,
+    Alarm = 0x09,
+    Clkout = 0x0D,
+    TimerControl = 0x0E
+}
+
+const ALARM_INTERRUPT_ENABLE: u8 = 0b0000_0010;
+const TIMER_INTERRUPT_ENABLE: u8 = 0b0000_0001;
+const ALARM_FLAG: u8 = 0b0000_1000;
+const TIMER_FLAG: u8 = 0b0000_0100;
+
+const VOLTAGE_LOW: u8 = 0b1000_0000;
+const CENTURY: u8 = 0b1000_0000;
+
+const ALARM_DISABLE: u8 = 0b1000_0000;
+const CLKOUT_ENABLE: u8 = 0b1000_0000;
+const TIMER_ENABLE: u8 = 0b1000_0000;
+
+const MIN_YEAR: u16 = 2000;
+const MAX_YEAR: u16 = 2199;
+
+/// Frequency of CLKOUT pin (FD1, FD0)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ClkoutFrequency {
+    _32768Hz,
+    _1024Hz,
+    _32Hz,
+    _1Hz
+}
+
+/// Source clock of countdown timer (TD1, TD0)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TimerFrequency {
+    _4096Hz,
+    _64Hz,
+    _1Hz,
+    /// One tick per minute
+    _1_60Hz
+}
+
+/// Alarm, fields set to `None` are not compared
+///
+/// Alarm fires when all set fields match, at 00 seconds of minute.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Alarm {
+    pub minutes: Option<u8>,
+    pub hours: Option<u8>,
+    /// Day of month [1-31]
+    pub day: Option<u8>,
+    pub weekday: Option<Weekday>
+}
+
+/// PCF8563 low power real time clock
+///
+/// # Example
+///
+/// ```no_run
+/// # use embedded_hal::blocking::i2c::{Write, WriteRead};
+/// # use device_drivers::i2c::rtc::datetime::DateTime;
+/// # fn example<I2C, E>(i2c: I2C, build_time: DateTime)
+/// #     where I2C: Write<Error = E> + WriteRead<Error = E>, E: core::fmt::Debug
+/// # {
+/// use device_drivers::i2c::rtc::pcf8563::{Rtc, DEFAULT_ADDRESS, TimerFrequency};
+/// use device_drivers::i2c::rtc::traits::RtcTrait;
+///
+/// let mut rtc = Rtc::new(i2c, DEFAULT_ADDRESS);
+/// if !rtc.is_time_valid().unwrap() {
+///     rtc.set(&build_time).unwrap();
+/// }
+///
+/// // interrupt on INT pin in 10 seconds
+/// rtc.set_timer(TimerFrequency::_1Hz, 10).unwrap();
+/// rtc.enable_timer_interrupt(true).unwrap();
+/// # }
+/// ```
+pub struct Rtc<I2CType> {
+    i2c: I2CType,
+    address: u8,
+    first_weekday: Weekday
+}
+
+#[derive(Debug)]
+pub enum RtcError<E> {
+    WrongValue,
+    /// Error of i2c bus
+    I2cError(E),
+    /// Supply voltage dropped (VL), time is invalid until next `set`
+    VoltageLow
+}
+
+impl<I2CType, E> Rtc<I2CType>
+    where
+        I2CType: Write<Error = E> + WriteRead<Error = E>
+{
+    pub fn new(i2c: I2CType, address: u8) -> Self {
+        Rtc {
+            i2c,
+            address,
+            first_weekday: Weekday::Sunday
+        }
+    }
+
+    /// Set weekday stored as 0 in weekday register (Sunday by default)
+    pub fn first_weekday(mut self, first: Weekday) -> Self {
+        self.first_weekday = first;
+        self
+    }
+
+    /// Write control/status 2, flags are written as 1 (no change) unless cleared
+    ///
+    /// Writing back flags as read could clear flag set in between.
+    fn update_control2(&mut self, mask: u8, value: u8, clear: u8) -> Result<(), RtcError<E>> {
+        let current = self.read_register(ControlData::Control2 as u8)?;
+        let value = (current & !mask) | (value & mask) | ALARM_FLAG | TIMER_FLAG;
+
+        self.write_register(ControlData::Control2 as u8, value & !clear)
+    }
+
+    fn enable_interrupt(&mut self, bit: u8, enable: bool) -> Result<(), RtcError<E>> {
+        self.update_control2(bit, if enable { bit } else { 0_u8 }, 0_u8)
+    }
+
+    fn weekday_to_register(&self, weekday: Weekday) -> u8 {
+        weekday.number_from(self.first_weekday) - 1
+    }
+
+    fn weekday_from_register(&self, value: u8) -> Result<Weekday, RtcError<E>> {
+        Weekday::from_number((value & 0x07) + 1, self.first_weekday)
+            .ok_or(RtcError::WrongValue)
+    }
+
+    /// Read voltage low flag (VL), time is valid if flag is not set
+    pub fn is_time_valid(&mut self) -> Result<bool, RtcError<E>> {
+        Ok(self.read_register(ControlData::Seconds as u8)? & VOLTAGE_LOW == 0)
+    }
+
+    /// Write alarm, interrupt is not changed
+    pub fn set_alarm(&mut self, alarm: &Alarm) -> Result<(), RtcError<E>> {
+        let field = |value: Option<u8>, min: u8, max: u8| match value {
+            Some(value) => check(value, min, max),
+            None => Ok(ALARM_DISABLE)
+        };
+
+        let data = [
+            ControlData::Alarm as u8,
+            field(alarm.minutes, 0, 59)?,
+            field(alarm.hours, 0, 23)?,
+            field(alarm.day, 1, 31)?,
+            field(alarm.weekday.map(|weekday| self.weekday_to_register(weekday)), 0, 6)?
+        ];
+
+        self.write(&data)
+    }
+
+    /// Read alarm
+    pub fn get_alarm(&mut self) -> Result<Alarm, RtcError<E>> {
+        let mut data = [0_u8; 4];
+
+        self.read_registers(ControlData::Alarm as u8, &mut data)?;
+
+        let field = |value: u8, mask: u8| match value & ALARM_DISABLE {
+            0 => Some(bcd_to_bin(value & mask)),
+            _ => None
+        };
+
+        Ok(Alarm {
+            minutes: field(data[0], 0x7F),
+            hours: field(data[1], 0x3F),
+            day: field(data[2], 0x3F),
+            weekday: match field(data[3], 0x07) {
+                Some(value) => Some(self.weekday_from_register(value)?),
+                None => None
+            }
+        })
+    }
+
+    /// Enable or disable alarm interrupt (AIE) on INT pin
+    pub fn enable_alarm_interrupt(&mut self, enable: bool) -> Result<(), RtcError<E>> {
+        self.enable_interrupt(ALARM_INTERRUPT_ENABLE, enable)
+    }
+
+    /// Read alarm flag (AF)
+    pub fn has_alarm_matched(&mut self) -> Result<bool, RtcError<E>> {
+        Ok(self.read_register(ControlData::Control2 as u8)? & ALARM_FLAG != 0)
+    }
+
+    /// Clear alarm flag (AF), releases INT pin
+    pub fn clear_alarm_matched(&mut self) -> Result<(), RtcError<E>> {
+        self.update_control2(0_u8, 0_u8, ALARM_FLAG)
+    }
+
+    /// Start countdown timer
+    ///
+    /// # Arguments
+    ///
+    /// * `frequency` - source clock of timer
+    /// * `value` - number of ticks until timer flag is set [1-255]
+    pub fn set_timer(&mut self, frequency: TimerFrequency, value: u8) -> Result<(), RtcError<E>> {
+        if value == 0 { return Err(RtcError::WrongValue); }
+
+        let source = match frequency {
+            TimerFrequency::_4096Hz => 0b00,
+            TimerFrequency::_64Hz => 0b01,
+            TimerFrequency::_1Hz => 0b10,
+            TimerFrequency::_1_60Hz => 0b11
+        };
+
+        self.write(&[ControlData::TimerControl as u8, TIMER_ENABLE | source, value])
+    }
+
+    /// Stop countdown timer, source clock is set to 1/60 Hz to save power
+    pub fn disable_timer(&mut self) -> Result<(), RtcError<E>> {
+        self.write_register(ControlData::TimerControl as u8, 0b11)
+    }
+
+    /// Enable or disable timer interrupt (TIE) on INT pin
+    pub fn enable_timer_interrupt(&mut self, enable: bool) -> Result<(), RtcError<E>> {
+        self.enable_interrupt(TIMER_INTERRUPT_ENABLE, enable)
+    }
+
+    /// Read timer flag (TF)
+    pub fn has_timer_expired(&mut self) -> Result<bool, RtcError<E>> {
+        Ok(self.read_register(ControlData::Control2 as u8)? & TIMER_FLAG != 0)
+    }
+
+    /// Clear timer flag (TF), releases INT pin
+    pub fn clear_timer_expired(&mut self) -> Result<(), RtcError<E>> {
+        self.update_control2(0_u8, 0_u8, TIMER_FLAG)
+    }
+
+    /// Output clock on CLKOUT pin (FE = 1)
+    pub fn set_clkout(&mut self, frequency: ClkoutFrequency) -> Result<(), RtcError<E>> {
+        let rate = match frequency {
+            ClkoutFrequency::_32768Hz => 0b00,
+            ClkoutFrequency::_1024Hz => 0b01,
+            ClkoutFrequency::_32Hz => 0b10,
+            ClkoutFrequency::_1Hz => 0b11
+        };
+
+        self.write_register(ControlData::Clkout as u8, CLKOUT_ENABLE | rate)
+    }
+
+    /// Disable CLKOUT pin (FE = 0), pin is high impedance
+    pub fn disable_clkout(&mut self) -> Result<(), RtcError<E>> {
+        self.write_register(ControlData::Clkout as u8, 0_u8)
+    }
+}
+
+impl_registers!();
+
+impl<E> ErrorTrait for RtcError<E>
+    where
+        E: Debug
+{
+    fn kind(&self) -> ErrorKind {
+        match self {
+            RtcError::WrongValue => ErrorKind::WrongValue,
+            RtcError::I2cError(_) => ErrorKind::Bus,
+            RtcError::VoltageLow => ErrorKind::TimeInvalid
+        }
+    }
+}
+
+impl<I2CType, E> RtcTrait<DateTime> for Rtc<I2CType>
+    where
+        I2CType: Write<Error = E> + WriteRead<Error = E>,
+        E: Debug
+{
+    type Error = RtcError<E>;
+
+    /// Write time and clear voltage low flag, years [2000-2199] are supported
+    fn set(&mut self, datetime: &DateTime) -> Result<(), Self::Error> {
+        let year = datetime.get_year()?;
+
+        if !(MIN_YEAR..=MAX_YEAR).contains(&year) { return Err(RtcError::WrongValue); }
+
+        let century = if year >= MIN_YEAR + 100 { CENTURY } else { 0_u8 };
+
+        let data_to_send = [
+            ControlData::Seconds as u8,
+            bin_to_bcd(datetime.get_seconds()?),
+            bin_to_bcd(datetime.get_minutes()?),
+            bin_to_bcd(datetime.get_hours()?),
+            bin_to_bcd(datetime.get_day()?),
+            self.weekday_to_register(datetime.get_weekday()?),
+            bin_to_bcd(datetime.get_month()?) | century,
+            bin_to_bcd((year % 100) as u8)
+        ];
+
+        self.write(&data_to_send)
+    }
+
+    /// Read time, fails with `VoltageLow` if time is invalid
+    fn get(&mut self) -> Result<DateTime, Self::Error> {
+        let mut data_to_read = [0_u8; 7];
+
+        self.read_registers(ControlData::Seconds as u8, &mut data_to_read)?;
+
+        if data_to_read[0] & VOLTAGE_LOW != 0 {
+            return Err(RtcError::VoltageLow);
+        }
+
+        let century = match data_to_read[5] & CENTURY {
+            0 => MIN_YEAR,
+            _ => MIN_YEAR + 100
+        };
+
+        Ok(
+            DateTime::from_ymd_hms(
+                century + (bcd_to_bin(data_to_read[6]) as u16),
+                bcd_to_bin(data_to_read[5] & 0x1F),
+                bcd_to_bin(data_to_read[3] & 0x3F),
+                bcd_to_bin(data_to_read[2] & 0x3F),
+                bcd_to_bin(data_to_read[1] & 0x7F),
+                bcd_to_bin(data_to_read[0] & 0x7F)
+            )?
+        )
+    }
+}
+
+impl<I2CType, E> AlarmTrait for Rtc<I2CType>
+    where
+        I2CType: Write<Error = E> + WriteRead<Error = E>,
+        E: Debug
+{
+    type Alarm = Alarm;
+
+    fn set_alarm(&mut self, alarm: &Alarm) -> Result<(), Self::Error> {
+        Rtc::set_alarm(self, alarm)
+    }
+
+    fn get_alarm(&mut self) -> Result<Alarm, Self::Error> {
+        Rtc::get_alarm(self)
+    }
+
+    fn enable_alarm_interrupt(&mut self, enable: bool) -> Result<(), Self::Error> {
+        Rtc::enable_alarm_interrupt(self, enable)
+    }
+
+    fn has_alarm_matched(&mut self) -> Result<bool, Self::Error> {
+        Rtc::has_alarm_matched(self)
+    }
+
+    fn clear_alarm_matched(&mut self) -> Result<(), Self::Error> {
+        Rtc::clear_alarm_matched(self)
+    }
+}
+
+impl<I2CType, E> SquareWaveTrait for Rtc<I2CType>
+    where
+        I2CType: Write<Error = E> + WriteRead<Error = E>,
+        E: Debug
+{
+    type Frequency = ClkoutFrequency;
+
+    fn set_square_wave(&mut self, frequency: ClkoutFrequency) -> Result<(), Self::Error> {
+        self.set_clkout(frequency)
+    }
+
+    fn disable_square_wave(&mut self) -> Result<(), Self::Error> {
+        self.disable_clkout()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i2c::mock::DumpI2c;
+
+    fn rtc() -> Rtc<DumpI2c> {
+        let mut i2c = DumpI2c::new(DEFAULT_ADDRESS, 0x10);
+        i2c.registers[ControlData::Seconds as usize] = VOLTAGE_LOW;
+
+        Rtc::new(i2c, DEFAULT_ADDRESS)
+    }
+
+    #[test]
+    fn test_time() {
+        let mut rtc = rtc();
+
+        assert!(matches!(rtc.get(), Err(RtcError::VoltageLow)));
+
+        let datetime = DateTime::from_ymd_hms(2121, 3, 14, 15, 9, 26).unwrap();
+        rtc.set(&datetime).unwrap();
+        assert_eq!(rtc.i2c.registers[0x02..0x09], [0x26, 0x09, 0x15, 0x14, 0x05, 0x83, 0x21]);
+        assert!(rtc.is_time_valid().unwrap());
+        assert_eq!(rtc.get().unwrap(), datetime);
+    }
+
+    #[test]
+    fn test_alarm() {
+        let mut rtc = rtc();
+
+        let alarm = Alarm { hours: Some(7), minutes: Some(30), weekday: Some(Weekday::Monday), ..Alarm::default() };
+        rtc.set_alarm(&alarm).unwrap();
+        assert_eq!(rtc.i2c.registers[0x09..0x0D], [0x30, 0x07, 0x80, 0x01]);
+        assert_eq!(rtc.get_alarm().unwrap(), alarm);
+
+        assert!(rtc.set_alarm(&Alarm { hours: Some(24), ..Alarm::default() }).is_err());
+
+        rtc.i2c.registers[0x01] = ALARM_FLAG | TIMER_FLAG;
+        rtc.clear_alarm_matched().unwrap();
+        assert!(!rtc.has_alarm_matched().unwrap());
+        assert!(rtc.has_timer_expired().unwrap());
+    }
+
+    #[test]
+    fn test_timer_and_clkout() {
+        let mut rtc = rtc();
+
+        rtc.set_timer(TimerFrequency::_1Hz, 10).unwrap();
+        assert_eq!(rtc.i2c.registers[0x0E..0x10], [0x82, 10]);
+        assert!(rtc.set_timer(TimerFrequency::_1Hz, 0).is_err());
+
+        rtc.set_clkout(ClkoutFrequency::_1Hz).unwrap();
+        assert_eq!(rtc.i2c.registers[0x0D], 0x83);
+    }
+}
@@ -0,0 +1,531 @@
+use core::fmt::Debug;
+use embedded_hal::blocking::{delay::DelayUs, i2c::{Write, WriteRead}};
+use super::traits::{RtcTrait, ErrorTrait, ErrorKind, SquareWaveTrait};
+use super::datetime::{DateTime, Weekday};
+use crate::i2c::rtc::traits::DateTimeTrait;
+use super::common::{Registers, impl_registers, bcd_to_bin, bin_to_bcd, decode_hours, encode_hours};
+
+pub use super::common::HourMode;
+
+/// Address of RV-3028-C7
+pub const DEFAULT_ADDRESS: u8 = 0x52;
+
+#[repr(u8)]
+enum ControlData {
+    Seconds = 0x00,
+    Status = 0x0E,
+    Control1 = 0x0F,
+    Control2 = 0x10,
+    EventControl = 0x13,
+    CountTimestamp = 0x14,
+    UnixTime = 0x1B,
+    EepromCommand = 0x27,
+    EepromClkout = 0x35,
+    EepromBackup = 0x37
+}
+
+const EEPROM_BUSY: u8 = 0b1000_0000;
+const BACKUP_SWITCH_FLAG: u8 = 0b0010_0000;
+const EVENT_FLAG: u8 = 0b0000_0010;
+const POWER_ON_RESET_FLAG: u8 = 0b0000_0001;
+
+const EEPROM_REFRESH_DISABLE: u8 = 0b0000_1000;
+
+const TIMESTAMP_ENABLE: u8 = 0b1000_0000;
+const HOUR_12: u8 = 0b0000_0010;
+
+const EVENT_HIGH: u8 = 0b0100_0000;
+const TIMESTAMP_RESET: u8 = 0b0000_0100;
+const TIMESTAMP_OVERWRITE: u8 = 0b0000_0010;
+
+const CLKOUT_ENABLE: u8 = 0b1000_0000;
+const CLKOUT_FREQUENCY: u8 = 0b0000_0111;
+const BACKUP_SWITCHOVER_MODE: u8 = 0b0000_1100;
+
+const EEPROM_UPDATE: u8 = 0x11;
+
+/// Time budget of EEPROM refresh or update in us, update of configuration
+/// takes up to 63 ms
+const EEPROM_BUSY_US: u32 = 100_000;
+
+/// Delay between polls of busy EEPROM in us
+const POLL_INTERVAL_US: u16 = 1000;
+
+/// Maximum number of read pairs of Unix time counter, counter changes once a
+/// second, so second pair always matches on working chip
+const MAX_UNIX_TIME_READS: u8 = 3;
+
+const MIN_YEAR: u16 = 2000;
+const MAX_YEAR: u16 = 2099;
+
+/// Frequency of CLKOUT pin (FD)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ClkoutFrequency {
+    _32768Hz,
+    _8192Hz,
+    _1024Hz,
+    _64Hz,
+    _32Hz,
+    _1Hz
+}
+
+/// Switching to backup supply on VBACKUP pin (BSM)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BackupSwitchover {
+    Disabled,
+    /// Switch when VDD drops below VBACKUP
+    Direct,
+    /// Switch when VDD drops below 2.0 V, for backup supply above VDD
+    Level
+}
+
+/// Edge of EVI pin which triggers event (EHL)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EventEdge {
+    Falling,
+    Rising
+}
+
+/// Time of event on EVI pin with number of events since reset
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Timestamp {
+    pub count: u8,
+    pub datetime: DateTime
+}
+
+/// RV-3028-C7 extreme low power real time clock
+///
+/// Configuration registers are mirrored in EEPROM, so setters of clock output
+/// and backup switchover write EEPROM and are kept after power loss.
+///
+/// # Example
+///
+/// ```no_run
+/// # use embedded_hal::blocking::{delay::DelayUs, i2c::{Write, WriteRead}};
+/// # use device_drivers::i2c::rtc::datetime::DateTime;
+/// # fn example<I2C, E, D>(i2c: I2C, delay: D, build_time: DateTime)
+/// #     where I2C: Write<Error = E> + WriteRead<Error = E>, E: core::fmt::Debug, D: DelayUs<u16>
+/// # {
+/// use device_drivers::i2c::rtc::rv3028::{Rtc, DEFAULT_ADDRESS, BackupSwitchover};
+/// use device_drivers::i2c::rtc::traits::RtcTrait;
+///
+/// let mut rtc = Rtc::new(i2c, DEFAULT_ADDRESS, delay);
+/// rtc.set_backup_switchover(BackupSwitchover::Level).unwrap();
+///
+/// if !rtc.is_time_valid().unwrap() {
+///     rtc.set(&build_time).unwrap();
+///     rtc.set_unix_time(build_time.to_unix_time_u32().unwrap()).unwrap();
+/// }
+/// # }
+/// ```
+pub struct Rtc<I2CType, DelayType> {
+    i2c: I2CType,
+    address: u8,
+    delay: DelayType,
+    hour_mode: HourMode,
+    first_weekday: Weekday
+}
+
+#[derive(Debug)]
+pub enum RtcError<E> {
+    WrongValue,
+    /// Error of i2c bus
+    I2cError(E),
+    /// Power on reset (PORF), time is invalid until next `set`
+    PowerOnReset,
+    /// EEPROM stayed busy or Unix time counter did not read consistently
+    Timeout
+}
+
+impl<I2CType, E, DelayType> Rtc<I2CType, DelayType>
+    where
+        I2CType: Write<Error = E> + WriteRead<Error = E>,
+        DelayType: DelayUs<u16>
+{
+    /// Return new rtc instance, delay is used while EEPROM is busy
+    pub fn new(i2c: I2CType, address: u8, delay: DelayType) -> Self {
+        Rtc {
+            i2c,
+            address,
+            delay,
+            hour_mode: HourMode::_24Hour,
+            first_weekday: Weekday::Sunday
+        }
+    }
+
+    /// Set mode of hours, `set` writes it to 12_24 bit of Control 2
+    pub fn hour_mode(mut self, mode: HourMode) -> Self {
+        self.hour_mode = mode;
+        self
+    }
+
+    /// Set weekday stored as 0 in Weekday register (Sunday by default), it
+    /// only counts, weekday returned by `get` is calculated from the date
+    pub fn first_weekday(mut self, first: Weekday) -> Self {
+        self.first_weekday = first;
+        self
+    }
+
+    /// Clear flag of status register, other flags are written as 1 (no change)
+    fn clear_status(&mut self, flag: u8) -> Result<(), RtcError<E>> {
+        self.write_register(ControlData::Status as u8, !flag)
+    }
+
+    /// Poll busy flag (EEBusy) of EEPROM refresh or update
+    fn wait_eeprom(&mut self) -> Result<(), RtcError<E>> {
+        let mut elapsed = 0_u32;
+
+        while self.read_register(ControlData::Status as u8)? & EEPROM_BUSY != 0 {
+            if elapsed >= EEPROM_BUSY_US { return Err(RtcError::Timeout); }
+
+            self.delay.delay_us(POLL_INTERVAL_US);
+            elapsed += POLL_INTERVAL_US as u32;
+        }
+
+        Ok(())
+    }
+
+    /// Write configuration register and copy configuration to EEPROM
+    ///
+    /// Automatic refresh from EEPROM is disabled meanwhile, so it can not
+    /// overwrite new value.
+    fn write_configuration(&mut self, register: u8, mask: u8, value: u8) -> Result<(), RtcError<E>> {
+        self.update_register(ControlData::Control1 as u8, EEPROM_REFRESH_DISABLE, EEPROM_REFRESH_DISABLE)?;
+
+        let result = self.wait_eeprom()
+            .and_then(|_| self.update_register(register, mask, value))
+            .and_then(|_| self.write_register(ControlData::EepromCommand as u8, 0_u8))
+            .and_then(|_| self.write_register(ControlData::EepromCommand as u8, EEPROM_UPDATE))
+            .and_then(|_| self.wait_eeprom());
+
+        self.update_register(ControlData::Control1 as u8, EEPROM_REFRESH_DISABLE, 0_u8)?;
+
+        result
+    }
+
+    /// Read power on reset flag (PORF), time is valid if flag is not set
+    pub fn is_time_valid(&mut self) -> Result<bool, RtcError<E>> {
+        Ok(self.read_register(ControlData::Status as u8)? & POWER_ON_RESET_FLAG == 0)
+    }
+
+    /// Read Unix time counter, it is independent from calendar
+    pub fn get_unix_time(&mut self) -> Result<u32, RtcError<E>> {
+        let mut data = [0_u8; 4];
+        let mut check = [0_u8; 4];
+
+        // counter is not buffered, read until two reads match
+        for _ in 0..MAX_UNIX_TIME_READS {
+            self.read_registers(ControlData::UnixTime as u8, &mut data)?;
+            self.read_registers(ControlData::UnixTime as u8, &mut check)?;
+
+            if data == check { return Ok(u32::from_le_bytes(data)); }
+        }
+
+        Err(RtcError::Timeout)
+    }
+
+    /// Write Unix time counter, calendar is not changed
+    pub fn set_unix_time(&mut self, time: u32) -> Result<(), RtcError<E>> {
+        let bytes = time.to_le_bytes();
+
+        self.write(&[ControlData::UnixTime as u8, bytes[0], bytes[1], bytes[2], bytes[3]])
+    }
+
+    /// Enable time stamp of events on EVI pin (TSE), previous time stamp is reset
+    ///
+    /// # Arguments
+    ///
+    /// * `edge` - edge of EVI pin which is recorded
+    /// * `overwrite` - keep last event instead of first one
+    pub fn enable_timestamp(&mut self, edge: EventEdge, overwrite: bool) -> Result<(), RtcError<E>> {
+        let high = if edge == EventEdge::Rising { EVENT_HIGH } else { 0_u8 };
+        let overwrite = if overwrite { TIMESTAMP_OVERWRITE } else { 0_u8 };
+
+        self.update_register(ControlData::EventControl as u8, EVENT_HIGH | TIMESTAMP_RESET | TIMESTAMP_OVERWRITE,
+                             high | TIMESTAMP_RESET | overwrite)?;
+
+        self.update_register(ControlData::Control2 as u8, TIMESTAMP_ENABLE, TIMESTAMP_ENABLE)
+    }
+
+    /// Disable time stamp (TSE)
+    pub fn disable_timestamp(&mut self) -> Result<(), RtcError<E>> {
+        self.update_register(ControlData::Control2 as u8, TIMESTAMP_ENABLE, 0_u8)
+    }
+
+    /// Read time stamp, `None` if no event was recorded
+    pub fn get_timestamp(&mut self) -> Result<Option<Timestamp>, RtcError<E>> {
+        let hour_12 = self.read_register(ControlData::Control2 as u8)? & HOUR_12 != 0;
+        let mut data = [0_u8; 7];
+
+        self.read_registers(ControlData::CountTimestamp as u8, &mut data)?;
+
+        if data[0] == 0 { return Ok(None); }
+
+        let datetime = DateTime::from_ymd_hms(
+            MIN_YEAR + (bcd_to_bin(data[6]) as u16),
+            bcd_to_bin(data[5] & 0x1F),
+            bcd_to_bin(data[4] & 0x3F),
+            decode_hours(data[3], hour_12),
+            bcd_to_bin(data[2] & 0x7F),
+            bcd_to_bin(data[1] & 0x7F)
+        )?;
+
+        Ok(Some(Timestamp { count: data[0], datetime }))
+    }
+
+    /// Read event flag (EVF)
+    pub fn has_event_occurred(&mut self) -> Result<bool, RtcError<E>> {
+        Ok(self.read_register(ControlData::Status as u8)? & EVENT_FLAG != 0)
+    }
+
+    /// Clear event flag (EVF)
+    pub fn clear_event(&mut self) -> Result<(), RtcError<E>> {
+        self.clear_status(EVENT_FLAG)
+    }
+
+    /// Set backup switchover mode and save it to EEPROM
+    pub fn set_backup_switchover(&mut self, mode: BackupSwitchover) -> Result<(), RtcError<E>> {
+        let value = match mode {
+            BackupSwitchover::Disabled => 0b00,
+            BackupSwitchover::Direct => 0b01,
+            BackupSwitchover::Level => 0b11
+        };
+
+        self.write_configuration(ControlData::EepromBackup as u8, BACKUP_SWITCHOVER_MODE, value << 2)
+    }
+
+    /// Read backup switchover mode
+    pub fn get_backup_switchover(&mut self) -> Result<BackupSwitchover, RtcError<E>> {
+        match (self.read_register(ControlData::EepromBackup as u8)? & BACKUP_SWITCHOVER_MODE) >> 2 {
+            0b01 => Ok(BackupSwitchover::Direct),
+            0b11 => Ok(BackupSwitchover::Level),
+            _ => Ok(BackupSwitchover::Disabled)
+        }
+    }
+
+    /// Read backup switch flag (BSF), set when chip switched to backup supply
+    pub fn has_backup_switched(&mut self) -> Result<bool, RtcError<E>> {
+        Ok(self.read_register(ControlData::Status as u8)? & BACKUP_SWITCH_FLAG != 0)
+    }
+
+    /// Clear backup switch flag (BSF)
+    pub fn clear_backup_switched(&mut self) -> Result<(), RtcError<E>> {
+        self.clear_status(BACKUP_SWITCH_FLAG)
+    }
+
+    /// Output clock on CLKOUT pin and save it to EEPROM
+    pub fn set_clkout(&mut self, frequency: ClkoutFrequency) -> Result<(), RtcError<E>> {
+        self.write_configuration(ControlData::EepromClkout as u8, CLKOUT_ENABLE | CLKOUT_FREQUENCY,
+                                 CLKOUT_ENABLE | frequency as u8)
+    }
+
+    /// Disable CLKOUT pin and save it to EEPROM, pin is driven low
+    pub fn disable_clkout(&mut self) -> Result<(), RtcError<E>> {
+        self.write_configuration(ControlData::EepromClkout as u8, CLKOUT_ENABLE, 0_u8)
+    }
+}
+
+impl_registers!(DelayType);
+
+impl<E> ErrorTrait for RtcError<E>
+    where
+        E: Debug
+{
+    fn kind(&self) -> ErrorKind {
+        match self {
+            RtcError::WrongValue => ErrorKind::WrongValue,
+            RtcError::I2cError(_) => ErrorKind::Bus,
+            RtcError::PowerOnReset => ErrorKind::TimeInvalid,
+            RtcError::Timeout => ErrorKind::Timeout
+        }
+    }
+}
+
+impl<I2CType, E, DelayType> RtcTrait<DateTime> for Rtc<I2CType, DelayType>
+    where
+        I2CType: Write<Error = E> + WriteRead<Error = E>,
+        DelayType: DelayUs<u16>,
+        E: Debug
+{
+    type Error = RtcError<E>;
+
+    /// Write time and hour mode, clears power on reset flag
+    ///
+    /// Years [2000-2099] are supported, Unix time counter is not changed.
+    fn set(&mut self, datetime: &DateTime) -> Result<(), Self::Error> {
+        let year = datetime.get_year()?;
+
+        if !(MIN_YEAR..=MAX_YEAR).contains(&year) { return Err(RtcError::WrongValue); }
+
+        let data_to_send = [
+            ControlData::Seconds as u8,
+            bin_to_bcd(datetime.get_seconds()?),
+            bin_to_bcd(datetime.get_minutes()?),
+            encode_hours(datetime.get_hours()?, self.hour_mode, 0_u8)?,
+            datetime.get_weekday()?.number_from(self.first_weekday) - 1,
+            bin_to_bcd(datetime.get_day()?),
+            bin_to_bcd(datetime.get_month()?),
+            bin_to_bcd((year - MIN_YEAR) as u8)
+        ];
+
+        let hour_12 = match self.hour_mode {
+            HourMode::_24Hour => 0_u8,
+            HourMode::_12Hour => HOUR_12
+        };
+
+        self.update_register(ControlData::Control2 as u8, HOUR_12, hour_12)?;
+
+        self.write(&data_to_send)?;
+
+        self.clear_status(POWER_ON_RESET_FLAG)
+    }
+
+    /// Read time, fails with `PowerOnReset` if time is invalid
+    fn get(&mut self) -> Result<DateTime, Self::Error> {
+        let mut data_to_read = [0_u8; ControlData::Control2 as usize + 1];
+
+        self.read_registers(ControlData::Seconds as u8, &mut data_to_read)?;
+
+        if data_to_read[ControlData::Status as usize] & POWER_ON_RESET_FLAG != 0 {
+            return Err(RtcError::PowerOnReset);
+        }
+
+        let hour_12 = data_to_read[ControlData::Control2 as usize] & HOUR_12 != 0;
+
+        Ok(
+            DateTime::from_ymd_hms(
+                MIN_YEAR + (bcd_to_bin(data_to_read[6]) as u16),
+                bcd_to_bin(data_to_read[5] & 0x1F),
+                bcd_to_bin(data_to_read[4] & 0x3F),
+                decode_hours(data_to_read[2], hour_12),
+                bcd_to_bin(data_to_read[1] & 0x7F),
+                bcd_to_bin(data_to_read[0] & 0x7F)
+            )?
+        )
+    }
+}
+
+/// Clock output is saved to EEPROM
+impl<I2CType, E, DelayType> SquareWaveTrait for Rtc<I2CType, DelayType>
+    where
+        I2CType: Write<Error = E> + WriteRead<Error = E>,
+        DelayType: DelayUs<u16>,
+        E: Debug
+{
+    type Frequency = ClkoutFrequency;
+
+    fn set_square_wave(&mut self, frequency: ClkoutFrequency) -> Result<(), Self::Error> {
+        self.set_clkout(frequency)
+    }
+
+    fn disable_square_wave(&mut self) -> Result<(), Self::Error> {
+        self.disable_clkout()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i2c::mock::{Device, DumpI2c, MockDelay};
+
+    #[derive(Default)]
+    struct Rv3028 {
+        eeprom_updates: usize,
+        /// Unix time counter advances on every read
+        unix_tick: bool
+    }
+
+    impl Device for Rv3028 {
+        fn write(&mut self, registers: &mut [u8], pointer: usize, value: u8) {
+            match pointer {
+                0x0E => registers[0x0E] &= value,
+                0x27 if value == EEPROM_UPDATE => {
+                    assert_ne!(registers[0x0F] & EEPROM_REFRESH_DISABLE, 0);
+                    self.eeprom_updates += 1;
+                },
+                pointer => registers[pointer] = value
+            }
+        }
+
+        fn stop(&mut self, registers: &mut [u8], bytes: &[u8], read: bool) {
+            if read && bytes[0] == 0x1B && self.unix_tick {
+                let time = u32::from_le_bytes([registers[0x1B], registers[0x1C], registers[0x1D], registers[0x1E]]);
+                registers[0x1B..0x1F].copy_from_slice(&(time + 1).to_le_bytes());
+            }
+        }
+    }
+
+    fn rtc() -> Rtc<DumpI2c<Rv3028>, MockDelay> {
+        let mut i2c = DumpI2c::with_device(DEFAULT_ADDRESS, 0x40, Rv3028::default());
+        i2c.registers[ControlData::Status as usize] = POWER_ON_RESET_FLAG | BACKUP_SWITCH_FLAG;
+
+        Rtc::new(i2c, DEFAULT_ADDRESS, MockDelay::default())
+    }
+
+    #[test]
+    fn test_time() {
+        let mut rtc = rtc();
+
+        assert!(matches!(rtc.get(), Err(RtcError::PowerOnReset)));
+
+        let datetime = DateTime::from_ymd_hms(2021, 3, 14, 15, 9, 26).unwrap();
+        rtc.set(&datetime).unwrap();
+        assert_eq!(rtc.i2c.registers[0x00..0x07], [0x26, 0x09, 0x15, 0x00, 0x14, 0x03, 0x21]);
+        assert_eq!(rtc.i2c.registers[0x0E], BACKUP_SWITCH_FLAG);
+        assert_eq!(rtc.get().unwrap(), datetime);
+
+        rtc.set_unix_time(0x6050_2A4E).unwrap();
+        assert_eq!(rtc.i2c.registers[0x1B..0x1F], [0x4E, 0x2A, 0x50, 0x60]);
+        assert_eq!(rtc.get_unix_time().unwrap(), 0x6050_2A4E);
+    }
+
+    #[test]
+    fn test_timestamp() {
+        let mut rtc = rtc();
+
+        rtc.enable_timestamp(EventEdge::Rising, true).unwrap();
+        assert_eq!(rtc.i2c.registers[0x13], 0x46);
+        assert_eq!(rtc.i2c.registers[0x10], TIMESTAMP_ENABLE);
+        assert_eq!(rtc.get_timestamp().unwrap(), None);
+
+        rtc.i2c.registers[0x14..0x1B].copy_from_slice(&[2, 0x26, 0x09, 0x15, 0x14, 0x03, 0x21]);
+        assert_eq!(rtc.get_timestamp().unwrap(), Some(Timestamp {
+            count: 2,
+            datetime: DateTime::from_ymd_hms(2021, 3, 14, 15, 9, 26).unwrap()
+        }));
+    }
+
+    #[test]
+    fn test_configuration() {
+        let mut rtc = rtc();
+        rtc.i2c.registers[0x37] = 0x10;
+
+        rtc.set_backup_switchover(BackupSwitchover::Level).unwrap();
+        assert_eq!(rtc.i2c.registers[0x37], 0x1C);
+        assert_eq!(rtc.get_backup_switchover().unwrap(), BackupSwitchover::Level);
+
+        rtc.set_clkout(ClkoutFrequency::_1Hz).unwrap();
+        assert_eq!(rtc.i2c.registers[0x35], 0x85);
+
+        assert_eq!(rtc.i2c.device.eeprom_updates, 2);
+        assert_eq!(rtc.i2c.registers[0x0F] & EEPROM_REFRESH_DISABLE, 0);
+
+        assert!(rtc.has_backup_switched().unwrap());
+        rtc.clear_backup_switched().unwrap();
+        assert!(!rtc.has_backup_switched().unwrap());
+    }
+
+    #[test]
+    fn test_timeout() {
+        let mut rtc = rtc();
+
+        rtc.i2c.device.unix_tick = true;
+        assert!(matches!(rtc.get_unix_time(), Err(RtcError::Timeout)));
+
+        rtc.i2c.registers[0x0E] |= EEPROM_BUSY;
+        assert_eq!(rtc.set_backup_switchover(BackupSwitchover::Level).unwrap_err().kind(), ErrorKind::Timeout);
+        assert_eq!(rtc.i2c.device.eeprom_updates, 0);
+        assert_eq!(rtc.i2c.registers[0x0F] & EEPROM_REFRESH_DISABLE, 0);
+        assert_eq!(rtc.delay.elapsed_us, EEPROM_BUSY_US);
+    }
+}
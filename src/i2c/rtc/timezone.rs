@@ -0,0 +1,405 @@
+//! Time zones for showing local time of rtc which holds UTC
+//!
+//! # Example
+//!
+//! ```
+//! use device_drivers::i2c::rtc::datetime::DateTime;
+//! use device_drivers::i2c::rtc::timezone::TimeZone;
+//! use device_drivers::i2c::rtc::traits::DateTimeTrait;
+//!
+//! let zone = TimeZone::from_posix("CET-1CEST,M3.5.0,M10.5.0/3").unwrap();
+//!
+//! // UTC time as read by `rtc.get()`
+//! let utc = DateTime::from_ymd_hms(2021, 7, 1, 12, 0, 0).unwrap();
+//! let local = zone.to_local(&utc).unwrap();
+//! assert_eq!(local.get_hours().unwrap(), 14);
+//! ```
+
+use super::datetime::{DateTime, Weekday, days_in_month};
+use super::duration::Duration;
+use super::traits::DateTimeTrait;
+
+const SECONDS_IN_HOUR: i32 = 3600;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TimeZoneError {
+    /// POSIX TZ string is malformed
+    WrongFormat,
+    /// POSIX TZ string uses form which is not supported (Jn, n or implicit rules)
+    Unsupported,
+    /// Converted time is out of `DateTime` range
+    OutOfRange,
+    /// Local time is skipped by transition to daylight saving time
+    NonExistent
+}
+
+/// Transition on weekday of week of month at local time before transition
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TransitionRule {
+    /// Month [1-12]
+    pub month: u8,
+    /// Week of month [1-5], 5 is the last week
+    pub week: u8,
+    pub weekday: Weekday,
+    /// Seconds since local midnight, may be negative or more than one day
+    pub time: i32
+}
+
+impl TransitionRule {
+    /// Unix time of transition in year, `offset` is UTC offset before transition
+    fn unix_time(&self, year: u16, offset: i32) -> Result<i64, TimeZoneError> {
+        let first = Weekday::from_date(year, self.month, 1);
+        let mut day = 1 + (self.weekday as u8 + 7 - first as u8) % 7 + (self.week - 1) * 7;
+
+        while day > days_in_month(year, self.month) {
+            day -= 7;
+        }
+
+        let date = DateTime::from_ymd_hms(year, self.month, day, 0, 0, 0)
+            .map_err(|_| { TimeZoneError::OutOfRange })?;
+
+        Ok(date.to_unix_time() + self.time as i64 - offset as i64)
+    }
+}
+
+/// Daylight saving time with its offset and yearly transitions
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DstRule {
+    /// Offset from UTC in seconds (east is positive)
+    pub offset: i32,
+    pub start: TransitionRule,
+    pub end: TransitionRule
+}
+
+/// Time zone with fixed offset or daylight saving time rule
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TimeZone {
+    offset: i32,
+    dst: Option<DstRule>
+}
+
+impl TimeZone {
+    /// Coordinated universal time
+    pub const fn utc() -> Self {
+        TimeZone {
+            offset: 0,
+            dst: None
+        }
+    }
+
+    /// Fixed offset from UTC in seconds (east is positive)
+    pub const fn fixed(offset: i32) -> Self {
+        TimeZone {
+            offset,
+            dst: None
+        }
+    }
+
+    /// Standard offset from UTC in seconds with daylight saving time
+    pub const fn with_dst(offset: i32, dst: DstRule) -> Self {
+        TimeZone {
+            offset,
+            dst: Some(dst)
+        }
+    }
+
+    /// European Union rule: summer time from last Sunday of March to last
+    /// Sunday of October, transitions at 01:00 UTC
+    ///
+    /// # Arguments
+    ///
+    /// * `offset` - standard offset in hours (0 - WET, 1 - CET, 2 - EET)
+    pub const fn eu(offset: i8) -> Self {
+        let offset = offset as i32 * SECONDS_IN_HOUR;
+
+        TimeZone::with_dst(offset, DstRule {
+            offset: offset + SECONDS_IN_HOUR,
+            start: TransitionRule { month: 3, week: 5, weekday: Weekday::Sunday, time: SECONDS_IN_HOUR + offset },
+            end: TransitionRule { month: 10, week: 5, weekday: Weekday::Sunday, time: 2 * SECONDS_IN_HOUR + offset }
+        })
+    }
+
+    /// United States rule: daylight saving time from second Sunday of March
+    /// to first Sunday of November, transitions at 02:00 local time
+    ///
+    /// # Arguments
+    ///
+    /// * `offset` - standard offset in hours (-5 - Eastern, -8 - Pacific)
+    pub const fn us(offset: i8) -> Self {
+        let offset = offset as i32 * SECONDS_IN_HOUR;
+
+        TimeZone::with_dst(offset, DstRule {
+            offset: offset + SECONDS_IN_HOUR,
+            start: TransitionRule { month: 3, week: 2, weekday: Weekday::Sunday, time: 2 * SECONDS_IN_HOUR },
+            end: TransitionRule { month: 11, week: 1, weekday: Weekday::Sunday, time: 2 * SECONDS_IN_HOUR }
+        })
+    }
+
+    /// Parse POSIX TZ string, for example "EST5EDT,M3.2.0,M11.1.0"
+    ///
+    /// Supported form is `std offset [dst [offset] ,Mm.w.d[/time],Mm.w.d[/time]]`,
+    /// names may be quoted like `<+03>`.
+    pub fn from_posix(tz: &str) -> Result<Self, TimeZoneError> {
+        let mut parser = Parser { data: tz.as_bytes(), position: 0 };
+
+        parser.name()?;
+        let offset = -parser.offset()?;
+
+        if parser.is_end() {
+            return Ok(TimeZone::fixed(offset));
+        }
+
+        parser.name()?;
+
+        let dst_offset = match parser.peek() {
+            Some(b',') | None => offset + SECONDS_IN_HOUR,
+            _ => -parser.offset()?
+        };
+
+        if parser.is_end() { return Err(TimeZoneError::Unsupported); }
+
+        parser.expect(b',')?;
+        let start = parser.rule()?;
+        parser.expect(b',')?;
+        let end = parser.rule()?;
+
+        if !parser.is_end() { return Err(TimeZoneError::WrongFormat); }
+
+        Ok(TimeZone::with_dst(offset, DstRule { offset: dst_offset, start, end }))
+    }
+
+    /// Offset from UTC in seconds at UTC time
+    pub fn offset_at(&self, utc: &DateTime) -> Result<i32, TimeZoneError> {
+        match self.dst {
+            Some(dst) if self.is_dst(utc)? => Ok(dst.offset),
+            _ => Ok(self.offset)
+        }
+    }
+
+    /// Check that daylight saving time is in effect at UTC time
+    pub fn is_dst(&self, utc: &DateTime) -> Result<bool, TimeZoneError> {
+        let dst = match self.dst {
+            Some(dst) => dst,
+            None => return Ok(false)
+        };
+
+        let year = utc.get_year().map_err(|_| { TimeZoneError::OutOfRange })?;
+        let time = utc.to_unix_time();
+        let start = dst.start.unix_time(year, self.offset)?;
+        let end = dst.end.unix_time(year, dst.offset)?;
+
+        if start < end {
+            Ok(start <= time && time < end)
+        } else {
+            Ok(time < end || start <= time)
+        }
+    }
+
+    /// Convert UTC time to local time
+    pub fn to_local(&self, utc: &DateTime) -> Result<DateTime, TimeZoneError> {
+        let offset = self.offset_at(utc)?;
+
+        utc.checked_add(Duration::from_seconds(offset as i64))
+            .ok_or(TimeZoneError::OutOfRange)
+    }
+
+    /// Convert local time to UTC time
+    ///
+    /// Local time repeated by transition to standard time is resolved to the
+    /// earlier (daylight saving) instant.
+    pub fn to_utc(&self, local: &DateTime) -> Result<DateTime, TimeZoneError> {
+        let offsets = match self.dst {
+            Some(dst) => [dst.offset, self.offset],
+            None => [self.offset, self.offset]
+        };
+
+        for &offset in offsets.iter() {
+            let utc = local.checked_sub(Duration::from_seconds(offset as i64))
+                .ok_or(TimeZoneError::OutOfRange)?;
+
+            if self.offset_at(&utc)? == offset {
+                return Ok(utc);
+            }
+        }
+
+        Err(TimeZoneError::NonExistent)
+    }
+
+}
+
+struct Parser<'a> {
+    data: &'a [u8],
+    position: usize
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<u8> {
+        self.data.get(self.position).copied()
+    }
+
+    fn is_end(&self) -> bool {
+        self.position >= self.data.len()
+    }
+
+    fn next(&mut self) -> Option<u8> {
+        let byte = self.peek()?;
+        self.position += 1;
+        Some(byte)
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), TimeZoneError> {
+        match self.next() {
+            Some(b) if b == byte => Ok(()),
+            _ => Err(TimeZoneError::WrongFormat)
+        }
+    }
+
+    /// Skip zone name, alphabetic or quoted in angle brackets
+    fn name(&mut self) -> Result<(), TimeZoneError> {
+        let start = self.position;
+
+        if self.peek() == Some(b'<') {
+            while self.next().ok_or(TimeZoneError::WrongFormat)? != b'>' {}
+            return Ok(());
+        }
+
+        while self.peek().is_some_and(|b| b.is_ascii_alphabetic()) {
+            self.position += 1;
+        }
+
+        if self.position - start < 3 { return Err(TimeZoneError::WrongFormat); }
+
+        Ok(())
+    }
+
+    fn number(&mut self) -> Result<i32, TimeZoneError> {
+        let start = self.position;
+        let mut value = 0_i32;
+
+        while let Some(digit) = self.peek().filter(|b| b.is_ascii_digit()) {
+            value = value * 10 + (digit - b'0') as i32;
+            self.position += 1;
+
+            if self.position - start > 3 { return Err(TimeZoneError::WrongFormat); }
+        }
+
+        if self.position == start { return Err(TimeZoneError::WrongFormat); }
+
+        Ok(value)
+    }
+
+    /// Parse `[+|-]hh[:mm[:ss]]` to seconds
+    fn offset(&mut self) -> Result<i32, TimeZoneError> {
+        let sign = match self.peek() {
+            Some(b'-') => { self.position += 1; -1 },
+            Some(b'+') => { self.position += 1; 1 },
+            _ => 1
+        };
+
+        let mut seconds = self.number()? * SECONDS_IN_HOUR;
+
+        for &scale in [60, 1].iter() {
+            if self.peek() != Some(b':') { break; }
+
+            self.position += 1;
+            let value = self.number()?;
+
+            if value > 59 { return Err(TimeZoneError::WrongFormat); }
+
+            seconds += value * scale;
+        }
+
+        Ok(sign * seconds)
+    }
+
+    /// Parse `Mm.w.d[/time]`
+    fn rule(&mut self) -> Result<TransitionRule, TimeZoneError> {
+        match self.peek() {
+            Some(b'M') => self.position += 1,
+            Some(_) => return Err(TimeZoneError::Unsupported),
+            None => return Err(TimeZoneError::WrongFormat)
+        }
+
+        let month = self.number()?;
+        self.expect(b'.')?;
+        let week = self.number()?;
+        self.expect(b'.')?;
+        let weekday = self.number()?;
+
+        if !(1..=12).contains(&month) || !(1..=5).contains(&week) || weekday > 6 {
+            return Err(TimeZoneError::WrongFormat);
+        }
+
+        let time = match self.peek() {
+            Some(b'/') => {
+                self.position += 1;
+                self.offset()?
+            },
+            _ => 2 * SECONDS_IN_HOUR
+        };
+
+        Ok(TransitionRule {
+            month: month as u8,
+            week: week as u8,
+            weekday: Weekday::from_number(weekday as u8 + 1, Weekday::Sunday)
+                .ok_or(TimeZoneError::WrongFormat)?,
+            time
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn datetime(year: u16, month: u8, day: u8, hours: u8, minutes: u8) -> DateTime {
+        DateTime::from_ymd_hms(year, month, day, hours, minutes, 0).unwrap()
+    }
+
+    #[test]
+    fn test_posix_eu() {
+        let zone = TimeZone::from_posix("CET-1CEST,M3.5.0,M10.5.0/3").unwrap();
+        assert_eq!(zone, TimeZone::eu(1));
+
+        assert_eq!(zone.to_local(&datetime(2021, 3, 28, 0, 59)).unwrap(), datetime(2021, 3, 28, 1, 59));
+        assert_eq!(zone.to_local(&datetime(2021, 3, 28, 1, 0)).unwrap(), datetime(2021, 3, 28, 3, 0));
+        assert_eq!(zone.to_local(&datetime(2021, 10, 31, 0, 59)).unwrap(), datetime(2021, 10, 31, 2, 59));
+        assert_eq!(zone.to_local(&datetime(2021, 10, 31, 1, 0)).unwrap(), datetime(2021, 10, 31, 2, 0));
+    }
+
+    #[test]
+    fn test_posix_us() {
+        let zone = TimeZone::from_posix("EST5EDT,M3.2.0,M11.1.0").unwrap();
+        assert_eq!(zone, TimeZone::us(-5));
+
+        assert!(!zone.is_dst(&datetime(2021, 3, 14, 6, 59)).unwrap());
+        assert!(zone.is_dst(&datetime(2021, 3, 14, 7, 0)).unwrap());
+        assert_eq!(zone.offset_at(&datetime(2021, 7, 1, 0, 0)).unwrap(), -4 * 3600);
+    }
+
+    #[test]
+    fn test_posix_southern_and_fixed() {
+        let zone = TimeZone::from_posix("AEST-10AEDT,M10.1.0,M4.1.0/3").unwrap();
+        assert!(zone.is_dst(&datetime(2021, 1, 1, 0, 0)).unwrap());
+        assert!(!zone.is_dst(&datetime(2021, 7, 1, 0, 0)).unwrap());
+
+        let zone = TimeZone::from_posix("<+0530>-5:30").unwrap();
+        assert_eq!(zone, TimeZone::fixed(5 * 3600 + 1800));
+
+        assert_eq!(TimeZone::from_posix("EST5EDT").err(), Some(TimeZoneError::Unsupported));
+        assert_eq!(TimeZone::from_posix("EST5EDT,J60,J300").err(), Some(TimeZoneError::Unsupported));
+        assert_eq!(TimeZone::from_posix("E5").err(), Some(TimeZoneError::WrongFormat));
+        assert_eq!(TimeZone::from_posix("EST5EDT,M13.1.0,M11.1.0").err(), Some(TimeZoneError::WrongFormat));
+    }
+
+    #[test]
+    fn test_to_utc() {
+        let zone = TimeZone::eu(1);
+
+        assert_eq!(zone.to_utc(&datetime(2021, 7, 1, 12, 0)).unwrap(), datetime(2021, 7, 1, 10, 0));
+        assert_eq!(zone.to_utc(&datetime(2021, 12, 1, 12, 0)).unwrap(), datetime(2021, 12, 1, 11, 0));
+        assert_eq!(zone.to_utc(&datetime(2021, 3, 28, 2, 30)).err(), Some(TimeZoneError::NonExistent));
+        assert_eq!(zone.to_utc(&datetime(2021, 10, 31, 2, 30)).unwrap(), datetime(2021, 10, 31, 0, 30));
+        assert_eq!(zone.to_utc(&datetime(2021, 10, 31, 3, 0)).unwrap().get_hours().ok(), Some(2));
+    }
+}
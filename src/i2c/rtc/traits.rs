@@ -1,14 +1,100 @@
 
+use core::fmt::Debug;
+use super::datetime::{DateTime, Weekday};
+
+/// Kind of rtc error, common for all chips
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ErrorKind {
+    /// Value is out of range supported by chip
+    WrongValue,
+    /// Error of i2c bus
+    Bus,
+    /// Time was lost (oscillator stopped, power failed), it must be set again
+    TimeInvalid,
+    /// Chip did not finish operation in time
+    Timeout
+}
+
+/// Error of rtc driver
+pub trait ErrorTrait: Debug {
+    fn kind(&self) -> ErrorKind;
+}
+
+/// Time access of rtc, implemented by all chips
+///
+/// Capability traits below are implemented where chip supports them, other
+/// features are chip specific methods.
 pub trait RtcTrait<T>
     where
         T: DateTimeTrait
 {
-    type Error;
+    type Error: ErrorTrait;
 
     fn set(&mut self, datetime: &T) -> Result<(), Self::Error>;
     fn get(&mut self) -> Result<T, Self::Error>;
 }
 
+/// Alarm of rtc, chips with two alarms use first one, second one is
+/// available by chip specific methods
+pub trait AlarmTrait: RtcTrait<DateTime> {
+    /// Alarm with match modes of chip
+    type Alarm;
+
+    /// Write alarm, interrupt is not changed
+    fn set_alarm(&mut self, alarm: &Self::Alarm) -> Result<(), Self::Error>;
+
+    /// Read alarm
+    fn get_alarm(&mut self) -> Result<Self::Alarm, Self::Error>;
+
+    /// Enable or disable alarm output on interrupt pin
+    fn enable_alarm_interrupt(&mut self, enable: bool) -> Result<(), Self::Error>;
+
+    /// Read alarm flag
+    fn has_alarm_matched(&mut self) -> Result<bool, Self::Error>;
+
+    /// Clear alarm flag
+    fn clear_alarm_matched(&mut self) -> Result<(), Self::Error>;
+}
+
+/// Temperature sensor of rtc
+pub trait TemperatureTrait: RtcTrait<DateTime> {
+    /// Read temperature in degrees Celsius
+    fn get_temperature(&mut self) -> Result<f32, Self::Error>;
+}
+
+/// Square wave (clock) output of rtc
+pub trait SquareWaveTrait: RtcTrait<DateTime> {
+    /// Frequencies supported by chip
+    type Frequency;
+
+    /// Output square wave
+    fn set_square_wave(&mut self, frequency: Self::Frequency) -> Result<(), Self::Error>;
+
+    /// Stop square wave
+    fn disable_square_wave(&mut self) -> Result<(), Self::Error>;
+}
+
+/// Frequency correction of rtc oscillator
+pub trait CalibrationTrait: RtcTrait<DateTime> {
+    /// Read correction in tenths of ppm, positive value speeds clock up
+    fn get_correction(&mut self) -> Result<i32, Self::Error>;
+
+    /// Write correction in tenths of ppm, it is rounded to step of chip
+    fn set_correction(&mut self, correction: i32) -> Result<(), Self::Error>;
+}
+
+/// Battery backed memory of rtc
+pub trait NvramTrait: RtcTrait<DateTime> {
+    /// Size of memory in bytes
+    fn nvram_size(&self) -> usize;
+
+    /// Read memory from `offset`, buffer must fit in memory
+    fn read_nvram(&mut self, offset: u8, data: &mut [u8]) -> Result<(), Self::Error>;
+
+    /// Write memory from `offset`, data must fit in memory
+    fn write_nvram(&mut self, offset: u8, data: &[u8]) -> Result<(), Self::Error>;
+}
+
 /// Real-Time Clock / Calendar
 pub trait DateTimeTrait {
     /// Error type
@@ -32,6 +118,9 @@ pub trait DateTimeTrait {
     /// Read the year (e.g. 2000).
     fn get_year(&self) -> Result<u16, Self::Error>;
 
+    /// Read the day of week.
+    fn get_weekday(&self) -> Result<Weekday, Self::Error>;
+
     /// Set the seconds [0-59].
     fn set_seconds(&mut self, seconds: u8) -> Result<(), Self::Error>;
 
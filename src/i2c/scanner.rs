@@ -0,0 +1,210 @@
+//! Scanner of i2c bus which identifies devices supported by this crate
+//!
+//! Devices are probed by one byte read, nothing is written except register
+//! pointer of DS3231 candidate.
+//!
+//! # Example
+//!
+//! ```
+//! # use embedded_hal::blocking::{delay::DelayMs, i2c::{Read, Write}};
+//! # struct I2c;
+//! # impl Read for I2c {
+//! #     type Error = ();
+//! #     fn read(&mut self, address: u8, _buffer: &mut [u8]) -> Result<(), ()> {
+//! #         if address == 0x3F { Ok(()) } else { Err(()) }
+//! #     }
+//! # }
+//! # impl Write for I2c {
+//! #     type Error = ();
+//! #     fn write(&mut self, _address: u8, _bytes: &[u8]) -> Result<(), ()> { Ok(()) }
+//! # }
+//! # struct Delay;
+//! # impl DelayMs<u16> for Delay { fn delay_ms(&mut self, _ms: u16) {} }
+//! # let (mut i2c, delay) = (I2c, Delay);
+//! use device_drivers::i2c::scanner::find_lcd;
+//! use device_drivers::i2c::lcd::Lcd;
+//!
+//! let address = find_lcd(&mut i2c).unwrap_or(0x27);
+//! # assert_eq!(address, 0x3F);
+//! let mut lcd = Lcd::new(i2c, address, delay).build();
+//! ```
+
+use embedded_hal::blocking::i2c::{Read, Write};
+use heapless::{Vec, consts::U16};
+
+const FIRST_ADDRESS: u8 = 0x08;
+const LAST_ADDRESS: u8 = 0x77;
+
+const DS3231_ADDRESS: u8 = 0x68;
+const DS3231_REGISTERS: usize = 0x13;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DeviceKind {
+    /// PCF8574 lcd backpack (0x20-0x27)
+    Pcf8574Lcd,
+    /// PCF8574A lcd backpack (0x38-0x3F)
+    Pcf8574aLcd,
+    /// DS3231 real time clock (0x68), registers look valid
+    Ds3231,
+    /// AT24Cxx style eeprom candidate (0x50-0x57), model is not detected
+    Eeprom,
+    /// Device answered, but it is not supported
+    Unknown
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Device {
+    pub address: u8,
+    pub kind: DeviceKind
+}
+
+/// Check that device acknowledges its address
+pub fn probe<I2CType>(i2c: &mut I2CType, address: u8) -> bool
+    where
+        I2CType: Read
+{
+    let mut data = [0_u8];
+
+    i2c.read(address, &mut data).is_ok()
+}
+
+/// Identify device which acknowledged its address
+pub fn identify<I2CType>(i2c: &mut I2CType, address: u8) -> DeviceKind
+    where
+        I2CType: Read + Write
+{
+    match address {
+        0x20..=0x27 => DeviceKind::Pcf8574Lcd,
+        0x38..=0x3F => DeviceKind::Pcf8574aLcd,
+        0x50..=0x57 => DeviceKind::Eeprom,
+        DS3231_ADDRESS if is_ds3231(i2c) => DeviceKind::Ds3231,
+        _ => DeviceKind::Unknown
+    }
+}
+
+/// Scan whole bus, returns up to 16 devices
+pub fn scan<I2CType>(i2c: &mut I2CType) -> Vec<Device, U16>
+    where
+        I2CType: Read + Write
+{
+    let mut devices = Vec::new();
+
+    for address in FIRST_ADDRESS..=LAST_ADDRESS {
+        if !probe(i2c, address) { continue; }
+
+        let device = Device {
+            address,
+            kind: identify(i2c, address)
+        };
+
+        if devices.push(device).is_err() { break; }
+    }
+
+    devices
+}
+
+/// Find address of first PCF8574/PCF8574A lcd backpack
+pub fn find_lcd<I2CType>(i2c: &mut I2CType) -> Option<u8>
+    where
+        I2CType: Read
+{
+    (0x20..=0x27).chain(0x38..=0x3F)
+        .find(|&address| probe(i2c, address))
+}
+
+fn is_bcd(value: u8, max: u8) -> bool {
+    value & 0x0F <= 9 && value <= max
+}
+
+/// Check DS3231 registers: time is valid BCD, unused bits of status and
+/// temperature registers are zero (on DS1307 this is battery backed RAM).
+fn is_ds3231<I2CType>(i2c: &mut I2CType) -> bool
+    where
+        I2CType: Read + Write
+{
+    let mut regs = [0_u8; DS3231_REGISTERS];
+
+    if i2c.write(DS3231_ADDRESS, &[0_u8]).is_err() { return false; }
+    if i2c.read(DS3231_ADDRESS, &mut regs).is_err() { return false; }
+
+    let hours_valid = match regs[2] & 0x40 {
+        0 => is_bcd(regs[2] & 0x3F, 0x23),
+        _ => is_bcd(regs[2] & 0x1F, 0x12) && regs[2] & 0x1F != 0
+    };
+
+    is_bcd(regs[0] & 0x7F, 0x59)
+        && is_bcd(regs[1] & 0x7F, 0x59)
+        && hours_valid
+        && (1..=7).contains(&regs[3])
+        && is_bcd(regs[4], 0x31) && regs[4] != 0
+        && is_bcd(regs[5] & 0x1F, 0x12) && regs[5] & 0x1F != 0
+        && is_bcd(regs[6], 0x99)
+        && regs[0x0F] & 0x70 == 0
+        && regs[0x12] & 0x3F == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct DumpI2c {
+        ds3231: [u8; DS3231_REGISTERS],
+        pointer: usize
+    }
+
+    impl Write for DumpI2c {
+        type Error = ();
+
+        fn write(&mut self, address: u8, bytes: &[u8]) -> Result<(), ()> {
+            if address != DS3231_ADDRESS { return Err(()); }
+
+            self.pointer = bytes[0] as usize;
+            Ok(())
+        }
+    }
+
+    impl Read for DumpI2c {
+        type Error = ();
+
+        fn read(&mut self, address: u8, buffer: &mut [u8]) -> Result<(), ()> {
+            match address {
+                0x27 | 0x57 => Ok(()),
+                DS3231_ADDRESS => {
+                    for b in buffer.iter_mut() {
+                        *b = self.ds3231[self.pointer % DS3231_REGISTERS];
+                        self.pointer += 1;
+                    }
+                    Ok(())
+                },
+                _ => Err(())
+            }
+        }
+    }
+
+    #[test]
+    fn test_scan() {
+        let mut i2c = DumpI2c {
+            ds3231: [0x30, 0x15, 0x12, 0x03, 0x21, 0x10, 0x20, 0, 0, 0, 0, 0, 0, 0, 0x1C, 0x88, 0, 0x19, 0x40],
+            pointer: 0
+        };
+
+        let devices = scan(&mut i2c);
+
+        assert_eq!(devices.len(), 3);
+        assert_eq!(devices[0], Device { address: 0x27, kind: DeviceKind::Pcf8574Lcd });
+        assert_eq!(devices[1], Device { address: 0x57, kind: DeviceKind::Eeprom });
+        assert_eq!(devices[2], Device { address: 0x68, kind: DeviceKind::Ds3231 });
+
+        assert_eq!(find_lcd(&mut i2c), Some(0x27));
+    }
+
+    #[test]
+    fn test_ds1307_is_unknown() {
+        let mut i2c = DumpI2c {
+            ds3231: [0x30, 0x15, 0x12, 0x03, 0x21, 0x10, 0x20, 0x10, 0xAA, 0, 0, 0, 0, 0, 0, 0x55, 0, 0, 0xFF],
+            pointer: 0
+        };
+
+        assert_eq!(identify(&mut i2c, DS3231_ADDRESS), DeviceKind::Unknown);
+    }
+}
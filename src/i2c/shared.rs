@@ -0,0 +1,222 @@
+//! Proxies for sharing one i2c peripheral between several drivers
+//!
+//! # Example
+//!
+//! ```
+//! # use embedded_hal::blocking::{delay::DelayMs, i2c::{Write, WriteRead}};
+//! # struct I2c;
+//! # impl Write for I2c {
+//! #     type Error = ();
+//! #     fn write(&mut self, _address: u8, _bytes: &[u8]) -> Result<(), ()> { Ok(()) }
+//! # }
+//! # impl WriteRead for I2c {
+//! #     type Error = ();
+//! #     fn write_read(&mut self, _address: u8, _bytes: &[u8], _buffer: &mut [u8]) -> Result<(), ()> { Ok(()) }
+//! # }
+//! # struct Delay;
+//! # impl DelayMs<u16> for Delay { fn delay_ms(&mut self, _ms: u16) {} }
+//! # let (i2c, delay) = (I2c, Delay);
+//! use device_drivers::i2c::shared::BusManagerSimple;
+//! use device_drivers::i2c::lcd::Lcd;
+//! use device_drivers::i2c::rtc::ds3231::Rtc;
+//!
+//! let bus = BusManagerSimple::new(i2c);
+//!
+//! let mut lcd = Lcd::new(bus.acquire(), 0x27, delay).build();
+//! let mut rtc = Rtc::new(bus.acquire(), 0x68);
+//! ```
+//!
+//! `BusManagerSimple` is for drivers used from one execution context only.
+//! With `critical-section` feature `BusManagerCriticalSection` is `Sync`, so
+//! proxies can be used from interrupt handlers once manager is moved to
+//! `&'static` storage at runtime (e.g. `cortex_m::singleton!`). Constructor
+//! is not `const`, manager can not be initialized in `static` item directly.
+
+use core::cell::RefCell;
+use embedded_hal::blocking::i2c::{Read, Write, WriteRead};
+
+/// Mutex which guards shared bus
+pub trait BusMutex {
+    /// Type of guarded bus
+    type Bus;
+
+    /// Create mutex which owns bus.
+    fn create(bus: Self::Bus) -> Self;
+
+    /// Lock mutex for the time of closure call.
+    fn lock<R, F: FnOnce(&mut Self::Bus) -> R>(&self, f: F) -> R;
+}
+
+/// Mutex for one execution context, panics on reentrant lock
+impl<T> BusMutex for RefCell<T> {
+    type Bus = T;
+
+    fn create(bus: T) -> Self {
+        RefCell::new(bus)
+    }
+
+    fn lock<R, F: FnOnce(&mut T) -> R>(&self, f: F) -> R {
+        f(&mut self.borrow_mut())
+    }
+}
+
+/// Mutex which locks bus inside critical section
+#[cfg(feature = "critical-section")]
+pub struct CriticalSectionMutex<T> {
+    bus: critical_section::Mutex<RefCell<T>>
+}
+
+#[cfg(feature = "critical-section")]
+impl<T> BusMutex for CriticalSectionMutex<T> {
+    type Bus = T;
+
+    fn create(bus: T) -> Self {
+        CriticalSectionMutex {
+            bus: critical_section::Mutex::new(RefCell::new(bus))
+        }
+    }
+
+    fn lock<R, F: FnOnce(&mut T) -> R>(&self, f: F) -> R {
+        critical_section::with(|cs| {
+            f(&mut self.bus.borrow_ref_mut(cs))
+        })
+    }
+}
+
+/// Owner of shared bus which hands out proxies to drivers
+pub struct BusManager<M> {
+    mutex: M
+}
+
+/// Bus manager for one execution context
+pub type BusManagerSimple<T> = BusManager<RefCell<T>>;
+
+/// Bus manager which can be shared with interrupt handlers
+#[cfg(feature = "critical-section")]
+pub type BusManagerCriticalSection<T> = BusManager<CriticalSectionMutex<T>>;
+
+impl<M> BusManager<M>
+    where
+        M: BusMutex
+{
+    /// Return new bus manager
+    ///
+    /// # Arguments
+    ///
+    /// * `bus` - i2c peripheral to share
+    pub fn new(bus: M::Bus) -> Self {
+        BusManager {
+            mutex: M::create(bus)
+        }
+    }
+
+    /// Return new proxy to pass to driver instead of i2c
+    pub fn acquire(&self) -> I2cProxy<'_, M> {
+        I2cProxy {
+            mutex: &self.mutex
+        }
+    }
+}
+
+/// I2c proxy which locks shared bus for each transaction
+pub struct I2cProxy<'a, M> {
+    mutex: &'a M
+}
+
+impl<'a, M> Clone for I2cProxy<'a, M> {
+    fn clone(&self) -> Self {
+        I2cProxy {
+            mutex: self.mutex
+        }
+    }
+}
+
+impl<'a, M> Write for I2cProxy<'a, M>
+    where
+        M: BusMutex,
+        M::Bus: Write
+{
+    type Error = <M::Bus as Write>::Error;
+
+    fn write(&mut self, address: u8, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.mutex.lock(|bus| bus.write(address, bytes))
+    }
+}
+
+impl<'a, M> Read for I2cProxy<'a, M>
+    where
+        M: BusMutex,
+        M::Bus: Read
+{
+    type Error = <M::Bus as Read>::Error;
+
+    fn read(&mut self, address: u8, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        self.mutex.lock(|bus| bus.read(address, buffer))
+    }
+}
+
+impl<'a, M> WriteRead for I2cProxy<'a, M>
+    where
+        M: BusMutex,
+        M::Bus: WriteRead
+{
+    type Error = <M::Bus as WriteRead>::Error;
+
+    fn write_read(&mut self, address: u8, bytes: &[u8], buffer: &mut [u8]) -> Result<(), Self::Error> {
+        self.mutex.lock(|bus| bus.write_read(address, bytes, buffer))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct DumpI2c {
+        writes: Vec<(u8, Vec<u8>)>
+    }
+
+    impl Write for DumpI2c {
+        type Error = ();
+
+        fn write(&mut self, address: u8, bytes: &[u8]) -> Result<(), ()> {
+            self.writes.push((address, bytes.to_vec()));
+            Ok(())
+        }
+    }
+
+    impl Read for DumpI2c {
+        type Error = ();
+
+        fn read(&mut self, address: u8, buffer: &mut [u8]) -> Result<(), ()> {
+            for b in buffer.iter_mut() { *b = address; }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_proxies_share_bus() {
+        let bus = BusManagerSimple::new(DumpI2c { writes: Vec::new() });
+
+        let mut first = bus.acquire();
+        let mut second = bus.acquire();
+
+        first.write(0x27, &[1, 2]).unwrap();
+        second.write(0x68, &[3]).unwrap();
+
+        let mut data = [0_u8; 2];
+        second.read(0x68, &mut data).unwrap();
+        assert_eq!(data, [0x68, 0x68]);
+
+        bus.mutex.lock(|i2c| {
+            assert_eq!(i2c.writes, vec![(0x27, vec![1, 2]), (0x68, vec![3])]);
+        });
+    }
+
+    #[cfg(feature = "critical-section")]
+    #[test]
+    fn test_critical_section_manager_is_sync() {
+        fn assert_sync<T: Sync>() {}
+
+        assert_sync::<BusManagerCriticalSection<DumpI2c>>();
+    }
+}
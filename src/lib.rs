@@ -2,6 +2,7 @@
 
 pub mod i2c;
 pub mod uart;
+pub mod display;
 
 /*
 /// Example test